@@ -0,0 +1,55 @@
+use std::sync::mpsc::{self, SyncSender};
+
+use kira::manager::{
+    backend::cpal::{CpalBackend, Error as BackendError},
+    AudioManager, AudioManagerSettings,
+};
+use snake::GameEvent;
+
+use super::{SoundPack, Volume};
+
+const QUEUED_EVENTS: usize = 16;
+
+/// Plays a [`SoundPack`] for the events of a board on a background thread.
+pub struct Audio {
+    sender: SyncSender<GameEvent>,
+    volume: Volume,
+}
+
+impl Audio {
+    /// Fails when no audio device is available, in which case the game
+    /// should simply run without a sender.
+    pub fn new(pack: SoundPack) -> Result<Self, BackendError> {
+        let mut manager = AudioManager::<CpalBackend>::new(AudioManagerSettings::default())?;
+        let (sender, receiver) = mpsc::sync_channel(QUEUED_EVENTS);
+        let volume = Volume::new();
+        let thread_volume = volume.clone();
+
+        std::thread::spawn(move || {
+            while let Ok(event) = receiver.recv() {
+                let sound = match event {
+                    GameEvent::FoodEaten(_) => pack.eat(),
+                    GameEvent::SnakeDied => pack.die(),
+                    GameEvent::SnakeChangedDirection => pack.turn(),
+                    GameEvent::BoardCleared => pack.eat(),
+                    GameEvent::FoodExpired(_) | GameEvent::Scored { .. } => continue,
+                };
+                if !thread_volume.muted() {
+                    let _ = manager.play(sound.volume(thread_volume.amplitude()));
+                }
+            }
+        });
+
+        Ok(Self { sender, volume })
+    }
+
+    /// A sender to subscribe to a board. Events arriving while the queue is
+    /// full are dropped instead of stalling the game.
+    pub fn sender(&self) -> SyncSender<GameEvent> {
+        self.sender.clone()
+    }
+
+    pub fn volume(&self) -> &Volume {
+        &self.volume
+    }
+}
@@ -0,0 +1,7 @@
+mod audio;
+mod pack;
+mod volume;
+
+pub use audio::*;
+pub use pack::*;
+pub use volume::*;
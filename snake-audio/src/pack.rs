@@ -0,0 +1,105 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+};
+
+use kira::sound::{static_sound::StaticSoundData, FromFileError};
+
+const EAT_SOUND: &[u8] = include_bytes!("../../assets/eat.mp3");
+const DIE_SOUND: &[u8] = include_bytes!("../../assets/die.mp3");
+const MOVE_SOUND: &[u8] = include_bytes!("../../assets/move.mp3");
+
+#[derive(Debug)]
+pub struct SoundPackError {
+    path: PathBuf,
+    source: FromFileError,
+}
+
+impl SoundPackError {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl fmt::Display for SoundPackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.source)
+    }
+}
+
+impl Error for SoundPackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The sounds played for game events.
+///
+/// A pack directory may contain `eat.mp3`, `die.mp3` and `move.mp3`. Any file
+/// that is missing keeps the built-in sound.
+#[derive(Clone)]
+pub struct SoundPack {
+    eat: StaticSoundData,
+    die: StaticSoundData,
+    turn: StaticSoundData,
+}
+
+impl SoundPack {
+    pub fn builtin() -> Self {
+        Self {
+            eat: embedded(EAT_SOUND),
+            die: embedded(DIE_SOUND),
+            turn: embedded(MOVE_SOUND),
+        }
+    }
+
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self, SoundPackError> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(SoundPackError {
+                path: dir.to_owned(),
+                source: FromFileError::IoError(io::ErrorKind::NotFound.into()),
+            });
+        }
+
+        let mut pack = Self::builtin();
+
+        for (name, sound) in [
+            ("eat.mp3", &mut pack.eat),
+            ("die.mp3", &mut pack.die),
+            ("move.mp3", &mut pack.turn),
+        ] {
+            let path = dir.join(name);
+            if path.is_file() {
+                *sound = StaticSoundData::from_file(&path)
+                    .map_err(|source| SoundPackError { path, source })?;
+            }
+        }
+
+        Ok(pack)
+    }
+
+    pub fn eat(&self) -> &StaticSoundData {
+        &self.eat
+    }
+
+    pub fn die(&self) -> &StaticSoundData {
+        &self.die
+    }
+
+    pub fn turn(&self) -> &StaticSoundData {
+        &self.turn
+    }
+}
+
+impl Default for SoundPack {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+fn embedded(sound: &'static [u8]) -> StaticSoundData {
+    StaticSoundData::from_cursor(Cursor::new(sound)).unwrap()
+}
@@ -0,0 +1,54 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicU8, Ordering},
+    Arc,
+};
+
+const VOLUME_STEP: u8 = 10;
+
+/// Volume shared between a frontend and the audio thread.
+#[derive(Clone, Debug)]
+pub struct Volume {
+    level: Arc<AtomicU8>,
+    muted: Arc<AtomicBool>,
+}
+
+impl Volume {
+    pub fn new() -> Self {
+        Self {
+            level: Arc::new(AtomicU8::new(100)),
+            muted: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level.load(Ordering::Relaxed)
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted.load(Ordering::Relaxed)
+    }
+
+    pub fn toggle_mute(&self) {
+        self.muted.fetch_xor(true, Ordering::Relaxed);
+    }
+
+    pub fn raise(&self) {
+        let level = self.level().saturating_add(VOLUME_STEP).min(100);
+        self.level.store(level, Ordering::Relaxed);
+    }
+
+    pub fn lower(&self) {
+        let level = self.level().saturating_sub(VOLUME_STEP);
+        self.level.store(level, Ordering::Relaxed);
+    }
+
+    pub fn amplitude(&self) -> f64 {
+        self.level() as f64 / 100.
+    }
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,100 @@
+use snake::{ai::Pilot, Difficulty, Edges, HeadToHead};
+
+/// Command-line options shared by both frontends.
+pub struct Args {
+    pub seed: Option<u64>,
+    pub edges: Option<Edges>,
+    pub level: Option<String>,
+    pub food: Option<usize>,
+    pub classic: bool,
+    pub difficulty: Difficulty,
+    pub sounds: Option<String>,
+    pub no_sound: bool,
+    pub players: usize,
+    pub head_to_head: HeadToHead,
+    pub pilots: [Pilot; 2],
+}
+
+impl Args {
+    /// Parses the process arguments, printing usage for `program` and
+    /// exiting on invalid input.
+    pub fn parse(program: &str) -> Self {
+        let mut args = Args {
+            seed: None,
+            edges: None,
+            level: None,
+            food: None,
+            classic: false,
+            difficulty: Difficulty::default(),
+            sounds: None,
+            no_sound: std::env::var_os("SNAKE_NO_SOUND").is_some_and(|value| !value.is_empty()),
+            players: 1,
+            head_to_head: HeadToHead::default(),
+            pilots: [Pilot::Human; 2],
+        };
+        let mut iter = std::env::args().skip(1);
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--seed" => match iter.next().and_then(|seed| seed.parse().ok()) {
+                    Some(seed) => args.seed = Some(seed),
+                    None => usage(program, "--seed expects a number"),
+                },
+                "--edges" => match iter.next().map(|edges| edges.parse()) {
+                    Some(Ok(edges)) => args.edges = Some(edges),
+                    Some(Err(error)) => usage(program, &error),
+                    None => usage(program, "--edges expects a value"),
+                },
+                "--level" => match iter.next() {
+                    Some(path) => args.level = Some(path),
+                    None => usage(program, "--level expects a file"),
+                },
+                "--food" => match iter.next().and_then(|food| food.parse().ok()) {
+                    Some(food) if food > 0 => args.food = Some(food),
+                    _ => usage(program, "--food expects a positive number"),
+                },
+                "--classic" => args.classic = true,
+                "--difficulty" => match iter.next().map(|difficulty| difficulty.parse()) {
+                    Some(Ok(difficulty)) => args.difficulty = difficulty,
+                    Some(Err(error)) => usage(program, &error),
+                    None => usage(program, "--difficulty expects a value"),
+                },
+                "--sounds" => match iter.next() {
+                    Some(path) => args.sounds = Some(path),
+                    None => usage(program, "--sounds expects a directory"),
+                },
+                "--no-sound" => args.no_sound = true,
+                "--players" => match iter.next().and_then(|players| players.parse().ok()) {
+                    Some(players @ 1..=2) => args.players = players,
+                    _ => usage(program, "--players expects 1 or 2"),
+                },
+                "--head-to-head" => match iter.next().map(|rule| rule.parse()) {
+                    Some(Ok(rule)) => args.head_to_head = rule,
+                    Some(Err(error)) => usage(program, &error),
+                    None => usage(program, "--head-to-head expects a value"),
+                },
+                "--player1" | "--player2" => match iter.next().map(|pilot| pilot.parse()) {
+                    Some(Ok(pilot)) => args.pilots[usize::from(arg == "--player2")] = pilot,
+                    Some(Err(error)) => usage(program, &error),
+                    None => usage(program, &format!("{arg} expects a value")),
+                },
+                _ => usage(program, &format!("unknown argument `{arg}`")),
+            }
+        }
+
+        if args.players > 1 && args.level.is_some() {
+            usage(program, "--level only supports one player");
+        }
+        if args.players < 2 && args.pilots[1] != Pilot::Human {
+            usage(program, "--player2 needs --players 2");
+        }
+
+        args
+    }
+}
+
+fn usage(program: &str, error: &str) -> ! {
+    eprintln!("error: {error}");
+    eprintln!("usage: {program} [--seed <number>] [--edges <wrap|walls|left-right|top-bottom>] [--level <file>] [--food <number>] [--classic] [--difficulty <easy|normal|hard|insane>] [--sounds <dir>] [--no-sound] [--players <1|2>] [--head-to-head <both-crash|longer-wins>] [--player1 <human|autopilot|solver>] [--player2 <human|autopilot|solver>]");
+    std::process::exit(2);
+}
@@ -0,0 +1,27 @@
+use snake::{ai::Seat, Direction, Game};
+
+/// The two sets of keys that steer a snake.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Keys {
+    Wasd,
+    Arrows,
+}
+
+impl Keys {
+    /// The player these keys steer. With two players the arrow keys drive the
+    /// second snake, otherwise both sets drive the only one.
+    pub fn player(self, game: &Game) -> usize {
+        match self {
+            Keys::Wasd => 0,
+            Keys::Arrows => game.players() - 1,
+        }
+    }
+}
+
+/// Turns the snake steered by `keys`, unless a bot has taken its seat.
+pub fn steer(game: &mut Game, seats: &[Seat], keys: Keys, direction: Direction) {
+    let player = keys.player(game);
+    if seats[player].is_human() {
+        game.turn_player(player, direction);
+    }
+}
@@ -0,0 +1,24 @@
+use snake::Game;
+
+/// Every player's points, `Score: 12` alone or `P1: 12  P2: 7` together.
+pub fn score_line(game: &Game) -> String {
+    match game.scores() {
+        [score] => format!("Score: {}", score.points()),
+        scores => scores
+            .iter()
+            .enumerate()
+            .map(|(player, score)| format!("P{}: {}", player + 1, score.points()))
+            .collect::<Vec<_>>()
+            .join("  "),
+    }
+}
+
+/// Prefix for the score breakdown, which follows the first player, so it says
+/// so once there are more.
+pub fn breakdown_label(game: &Game) -> &'static str {
+    if game.players() > 1 {
+        "P1 "
+    } else {
+        ""
+    }
+}
@@ -0,0 +1,9 @@
+mod args;
+mod controls;
+mod hud;
+mod setup;
+
+pub use args::*;
+pub use controls::*;
+pub use hud::*;
+pub use setup::*;
@@ -0,0 +1,134 @@
+use snake::{ai::Seat, Board, Direction, Game, Level, Snake, SnakeConfig, SpawnError};
+use snake_audio::{Audio, SoundPack};
+
+use super::Args;
+
+/// Loads and builds the level at `path`, on the edges from `--edges` if given.
+pub fn load_level(path: &str, args: &Args) -> (Board, Snake) {
+    let level = Level::load(path).and_then(|mut level| {
+        if let Some(edges) = args.edges {
+            level.set_edges(edges);
+        }
+        level.build(args.seed)
+    });
+    match level {
+        Ok(level) => level,
+        Err(error) => {
+            eprintln!("error: {path}: {error}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Starts playing sounds unless turned off. A broken sound pack is an error,
+/// a missing audio device only a warning.
+pub fn start_audio(args: &Args) -> Option<Audio> {
+    if args.no_sound {
+        return None;
+    }
+
+    let pack = match args.sounds.as_deref().map(SoundPack::load) {
+        Some(Ok(pack)) => pack,
+        Some(Err(error)) => {
+            eprintln!("error: {error}");
+            std::process::exit(1);
+        }
+        None => SoundPack::builtin(),
+    };
+
+    match Audio::new(pack) {
+        Ok(audio) => Some(audio),
+        Err(error) => {
+            eprintln!("warning: sound disabled: {error}");
+            None
+        }
+    }
+}
+
+fn spawn_snakes(board: &Board, players: usize) -> Vec<Snake> {
+    let configs = [
+        SnakeConfig::default(),
+        SnakeConfig::new()
+            .position(
+                board.columns().saturating_sub(6),
+                board.rows().saturating_sub(3),
+            )
+            .direction(Direction::Left),
+    ];
+
+    let mut snakes: Vec<Snake> = Vec::new();
+    for config in configs.into_iter().take(players) {
+        let snake = Snake::new(config, board).and_then(|snake| {
+            // The board only holds the layout until the game starts, so check
+            // the snakes against each other by hand.
+            let overlap = snake.segments().find(|segment| {
+                snakes
+                    .iter()
+                    .flat_map(Snake::segments)
+                    .any(|other| (other.col(), other.row()) == (segment.col(), segment.row()))
+            });
+            match overlap {
+                Some(segment) => Err(SpawnError::Blocked {
+                    col: segment.col(),
+                    row: segment.row(),
+                }),
+                None => Ok(snake),
+            }
+        });
+        match snake {
+            Ok(snake) => snakes.push(snake),
+            Err(error) => {
+                eprintln!("error: {error}");
+                std::process::exit(1);
+            }
+        }
+    }
+    snakes
+}
+
+/// A board of the given size with a snake for every player.
+pub fn new_board(args: &Args, rows: usize, columns: usize) -> (Board, Vec<Snake>) {
+    let mut board = match args.seed {
+        Some(seed) => Board::with_seed(rows, columns, seed),
+        None => Board::new(rows, columns),
+    };
+    if let Some(edges) = args.edges {
+        board.set_edges(edges);
+    }
+    let snakes = spawn_snakes(&board, args.players);
+    (board, snakes)
+}
+
+/// Applies the remaining options to the board and starts a game with a seat
+/// for every snake.
+pub fn start_game(
+    args: &Args,
+    mut board: Board,
+    snakes: Vec<Snake>,
+    audio: Option<&Audio>,
+) -> (Game, Vec<Seat>) {
+    if let Some(food) = args.food {
+        board.set_food_count(food);
+    }
+    board.set_special_food(!args.classic);
+    if let Some(audio) = audio {
+        board.subscribe(audio.sender());
+    }
+
+    let seats = args.pilots[..snakes.len()]
+        .iter()
+        .map(|&pilot| {
+            let mut seat = Seat::new(&board);
+            if !seat.set_pilot(pilot) {
+                eprintln!("error: the solver needs a board without walls and an even number of rows or columns");
+                std::process::exit(1);
+            }
+            seat
+        })
+        .collect();
+
+    let mut game = Game::with_snakes(board, snakes);
+    game.set_difficulty(args.difficulty);
+    game.set_head_to_head(args.head_to_head);
+    (game, seats)
+}
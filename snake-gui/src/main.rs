@@ -1,7 +1,75 @@
-use macroquad::prelude::*;
-use snake::{Board, CellType, Direction, Snake};
+use macroquad::{
+    input::utils::{register_input_subscriber, repeat_all_miniquad_input},
+    miniquad::EventHandler,
+    prelude::*,
+};
+use snake_audio::{Audio, Volume};
+use snake_cli::{
+    breakdown_label, load_level, new_board, score_line, start_audio, start_game, steer, Args, Keys,
+};
+
+use snake::{
+    ai::{Pilot, Seat},
+    Board, CellType, Direction, Edge, FoodKind, Game, Segment, Snake,
+};
 
 const BLOCK_SIZE: f32 = 30.;
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// Records whether the window lost focus, which miniquad reports as the
+/// window being minimized.
+#[derive(Default)]
+struct FocusLost(bool);
+
+impl EventHandler for FocusLost {
+    fn update(&mut self) {}
+    fn draw(&mut self) {}
+    fn window_minimized_event(&mut self) {
+        self.0 = true;
+    }
+}
+
+struct Interpolation {
+    enabled: bool,
+    previous: Vec<Vec<(usize, usize)>>,
+    alpha: f32,
+}
+
+impl Interpolation {
+    fn snapshot(&mut self, game: &Game) {
+        self.previous.resize_with(game.players(), Vec::new);
+        for (previous, snake) in self.previous.iter_mut().zip(game.snakes()) {
+            previous.clear();
+            previous.extend(
+                snake
+                    .segments()
+                    .map(|segment| (segment.col(), segment.row())),
+            );
+        }
+    }
+
+    fn position(&self, player: usize, index: usize, segment: &Segment) -> (f32, f32) {
+        let (col, row) = (segment.col() as f32, segment.row() as f32);
+        let previous = self
+            .previous
+            .get(player)
+            .and_then(|previous| previous.get(index));
+        let Some(&(prev_col, prev_row)) = previous else {
+            return (col, row);
+        };
+
+        let (prev_col, prev_row) = (prev_col as f32, prev_row as f32);
+        // Segments that wrapped around the board jump straight to their new cell.
+        if !self.enabled || (col - prev_col).abs() + (row - prev_row).abs() != 1. {
+            return (col, row);
+        }
+
+        (
+            prev_col + (col - prev_col) * self.alpha,
+            prev_row + (row - prev_row) * self.alpha,
+        )
+    }
+}
 
 fn window_conf() -> Conf {
     Conf {
@@ -13,56 +81,129 @@ fn window_conf() -> Conf {
 
 #[macroquad::main(window_conf)]
 async fn main() {
+    let args = Args::parse("snake-gui");
     let rows: usize = (600. / BLOCK_SIZE) as usize;
     let columns: usize = (600. / BLOCK_SIZE) as usize;
 
-    let mut board = Board::new(rows, columns, None);
-    let mut snake = Snake::new();
+    let audio = start_audio(&args);
 
-    board.generate_food();
+    let (board, snakes) = match &args.level {
+        Some(path) => {
+            let (board, snake) = load_level(path, &args);
+            (board, vec![snake])
+        }
+        None => new_board(&args, rows, columns),
+    };
+    let (mut game, mut seats) = start_game(&args, board, snakes, audio.as_ref());
 
-    let mut count = 0;
+    let mut interpolation = Interpolation {
+        enabled: false,
+        previous: Vec::new(),
+        alpha: 1.,
+    };
+    let mut show_path = false;
+    let mut accumulator = 0.;
+    let input = register_input_subscriber();
     loop {
         clear_background(BLACK);
 
         if is_key_pressed(KeyCode::Q) {
             break;
         }
+        if is_key_pressed(KeyCode::I) {
+            interpolation.enabled = !interpolation.enabled;
+        }
+        if let Some(audio) = &audio {
+            update_volume(audio.volume());
+        }
+        if is_key_pressed(KeyCode::B) {
+            seats[0].toggle(Pilot::Autopilot);
+        }
+        if is_key_pressed(KeyCode::C) {
+            seats[0].toggle(Pilot::Solver);
+        }
+        if is_key_pressed(KeyCode::H) {
+            show_path = !show_path;
+        }
 
-        draw_board(&board, &snake);
-        update_board(&mut board, &mut snake);
-        next_frame().await;
+        update_board(&mut game, &seats, input);
 
-        if count == 5 {
-            count = 0;
-            snake.update_movement(&mut board);
+        accumulator += (get_frame_time() as f64).min(MAX_FRAME_TIME);
+        let interval = game.tick_interval().as_secs_f64();
+        while accumulator >= interval {
+            accumulator -= interval;
+            interpolation.snapshot(&game);
+            game.step_with(&mut seats);
         }
+        interpolation.alpha = (accumulator / interval) as f32;
 
-        count += 1;
+        draw_board(
+            &game,
+            &interpolation,
+            audio.as_ref().map(Audio::volume),
+            &seats,
+            show_path,
+        );
+        next_frame().await;
     }
 }
 
-fn update_board(board: &mut Board, snake: &mut Snake) {
-    if !board.game_over() {
-        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
-            snake.change_direction(Direction::Up, board);
-        } else if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
-            snake.change_direction(Direction::Down, board);
-        } else if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) {
-            snake.change_direction(Direction::Left, board);
-        } else if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) {
-            snake.change_direction(Direction::Right, board);
+fn update_volume(volume: &Volume) {
+    if is_key_pressed(KeyCode::M) {
+        volume.toggle_mute();
+    }
+    if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+        volume.raise();
+    }
+    if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+        volume.lower();
+    }
+}
+
+fn update_board(game: &mut Game, seats: &[Seat], input: usize) {
+    let mut focus_lost = FocusLost::default();
+    repeat_all_miniquad_input(&mut focus_lost, input);
+    if !game.game_over() && !game.paused() && focus_lost.0 {
+        game.toggle_pause();
+    }
+
+    if !game.game_over() && (is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::P)) {
+        game.toggle_pause();
+    }
+
+    if !game.game_over() && !game.paused() {
+        let bindings = [
+            (KeyCode::W, Keys::Wasd, Direction::Up),
+            (KeyCode::S, Keys::Wasd, Direction::Down),
+            (KeyCode::A, Keys::Wasd, Direction::Left),
+            (KeyCode::D, Keys::Wasd, Direction::Right),
+            (KeyCode::Up, Keys::Arrows, Direction::Up),
+            (KeyCode::Down, Keys::Arrows, Direction::Down),
+            (KeyCode::Left, Keys::Arrows, Direction::Left),
+            (KeyCode::Right, Keys::Arrows, Direction::Right),
+        ];
+
+        for (key, keys, direction) in bindings {
+            if is_key_pressed(key) {
+                steer(game, seats, keys, direction);
+            }
         }
-        snake.update(board);
     }
 
-    if is_key_pressed(KeyCode::Space) && board.game_over() {
-        board.reset();
-        snake.reset();
+    if is_key_pressed(KeyCode::Space) && game.game_over() {
+        game.reset();
     }
 }
 
-fn draw_board(board: &Board, snake: &Snake) {
+fn draw_board(
+    game: &Game,
+    interpolation: &Interpolation,
+    volume: Option<&Volume>,
+    seats: &[Seat],
+    show_path: bool,
+) {
+    let board = game.board();
+
     let width = board.columns() as f32 * BLOCK_SIZE;
     let height = board.rows() as f32 * BLOCK_SIZE;
 
@@ -74,42 +215,165 @@ fn draw_board(board: &Board, snake: &Snake) {
         let y = BLOCK_SIZE * c.row() as f32 + offset_y;
 
         match c.cell_type() {
-            CellType::Empty => draw_rectangle(x, y, BLOCK_SIZE, BLOCK_SIZE, BLACK),
-            CellType::Snake => draw_rectangle(x, y, BLOCK_SIZE, BLOCK_SIZE, GREEN),
-            CellType::Food => draw_rectangle(x, y, BLOCK_SIZE, BLOCK_SIZE, RED),
-            CellType::SnakeHead => draw_rectangle(x, y, BLOCK_SIZE, BLOCK_SIZE, BLUE),
+            CellType::Empty | CellType::Snake(_) | CellType::SnakeHead(_) => {
+                draw_rectangle(x, y, BLOCK_SIZE, BLOCK_SIZE, BLACK)
+            }
+            CellType::Food(kind) => draw_rectangle(x, y, BLOCK_SIZE, BLOCK_SIZE, food_color(kind)),
+            CellType::Wall => draw_rectangle(x, y, BLOCK_SIZE, BLOCK_SIZE, GRAY),
         };
 
         draw_rectangle_lines(x, y, BLOCK_SIZE, BLOCK_SIZE, 1., WHITE);
     }
 
+    let center = |(col, row): (usize, usize)| {
+        (
+            BLOCK_SIZE * (col as f32 + 0.5) + offset_x,
+            BLOCK_SIZE * (row as f32 + 0.5) + offset_y,
+        )
+    };
+    for (player, seat) in seats.iter().enumerate() {
+        let (Pilot::Solver, Some(solver)) = (seat.pilot(), seat.solver()) else {
+            continue;
+        };
+        if !show_path || !game.on_board(player) {
+            continue;
+        }
+
+        let snake = &game.snakes()[player];
+        let path: Vec<(f32, f32)> = solver.path(board, snake).map(center).collect();
+        for pair in path.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            draw_line(x1, y1, x2, y2, 3., Color { a: 0.6, ..GOLD });
+        }
+    }
+
+    for (player, snake) in game.snakes().iter().enumerate() {
+        if !game.on_board(player) {
+            continue;
+        }
+        draw_snake(snake, player, interpolation, offset_x, offset_y);
+    }
     draw_rectangle_lines(offset_x, offset_y, width, height, 2., BLACK);
+    draw_border(board, offset_x, offset_y, width, height);
 
     let start_pos = height + offset_y;
+    let score = game.score();
+    let breakdown = score.breakdown();
+    let label = breakdown_label(game);
+    draw_text(score_line(game), offset_x, start_pos + 30., 30., WHITE);
     draw_text(
-        &format!("Score: {}", snake.score()),
-        offset_x,
+        format!(
+            "{label}Combo: x{} (best x{})",
+            score.multiplier(),
+            score.best_multiplier()
+        ),
+        offset_x + width / 2.,
         start_pos + 30.,
         30.,
         WHITE,
     );
     draw_text(
-        &format!("FPS: {}", get_fps()),
+        format!(
+            "{label}Food: {}  Combo: {}",
+            breakdown.food, breakdown.combo
+        ),
+        offset_x + width / 2.,
+        start_pos + 60.,
+        30.,
+        WHITE,
+    );
+    draw_text(
+        format!(
+            "{label}Time: {}  Penalty: {}",
+            breakdown.time, breakdown.penalty
+        ),
+        offset_x + width / 2.,
+        start_pos + 90.,
+        30.,
+        WHITE,
+    );
+    draw_text(
+        format!(
+            "Speed: {} ({})",
+            game.speed_level(),
+            game.difficulty().name()
+        ),
+        offset_x + width / 2.,
+        start_pos + 120.,
+        30.,
+        WHITE,
+    );
+    draw_text(
+        format!("FPS: {}", get_fps()),
         offset_x,
         start_pos + 60.,
         30.,
         WHITE,
     );
     draw_text(
-        &format!("{} seconds", get_time().floor()),
+        format!("{} seconds", get_time().floor()),
         offset_x,
         start_pos + 90.,
         30.,
         WHITE,
     );
+    if let Some(seed) = board.seed() {
+        draw_text(
+            format!("Seed: {seed}"),
+            offset_x,
+            start_pos + 120.,
+            30.,
+            WHITE,
+        );
+    }
+    draw_text(
+        format!(
+            "Smooth movement: {} (press <i> to toggle)",
+            if interpolation.enabled { "on" } else { "off" }
+        ),
+        offset_x,
+        start_pos + 150.,
+        30.,
+        WHITE,
+    );
+    let level = match volume {
+        None => "off".to_owned(),
+        Some(volume) if volume.muted() => "muted".to_owned(),
+        Some(volume) => format!("{}%", volume.level()),
+    };
+    draw_text(
+        format!("Volume: {level} (press <m> to mute, <-> or <+> to change)"),
+        offset_x,
+        start_pos + 180.,
+        30.,
+        WHITE,
+    );
+    let seat = &seats[0];
+    draw_text(
+        format!(
+            "Player 1: {} (press <b> for the autopilot, <c> for the solver)",
+            seat.pilot().name()
+        ),
+        offset_x,
+        start_pos + 210.,
+        30.,
+        WHITE,
+    );
+    let path = match (seat.solver(), show_path) {
+        (None, _) => "Solver: unavailable on this board",
+        (Some(_), true) => "Solver path: on (press <h> to toggle)",
+        (Some(_), false) => "Solver path: off (press <h> to toggle)",
+    };
+    draw_text(path, offset_x, start_pos + 240., 30., WHITE);
 
-    if board.game_over() {
-        draw_centered_text("GAME OVER", screen_width() / 2., offset_y - 80., 100., RED);
+    if game.victory() {
+        draw_centered_text(
+            "BOARD CLEARED",
+            screen_width() / 2.,
+            offset_y - 80.,
+            100.,
+            GOLD,
+        );
         draw_centered_text(
             "Press <space> to restart the game",
             screen_width() / 2.,
@@ -124,8 +388,47 @@ fn draw_board(board: &Board, snake: &Snake) {
             30.,
             WHITE,
         );
-    } else {
-        draw_centered_text("SNAKE", screen_width() / 2., offset_y - 80., 100., GREEN);
+    } else if board.game_over() {
+        let (title, color) = match (game.players(), game.winner()) {
+            (1, _) => ("GAME OVER".to_owned(), RED),
+            (_, Some(player)) => (
+                format!("PLAYER {} WINS", player + 1),
+                player_colors(player).0,
+            ),
+            (_, None) => ("DRAW".to_owned(), RED),
+        };
+        draw_centered_text(&title, screen_width() / 2., offset_y - 80., 100., color);
+        draw_centered_text(
+            "Press <space> to restart the game",
+            screen_width() / 2.,
+            offset_y - 40.,
+            30.,
+            WHITE,
+        );
+        draw_centered_text(
+            "Press <q> to quit the game",
+            screen_width() / 2.,
+            offset_y - 20.,
+            30.,
+            WHITE,
+        );
+    } else if board.paused() {
+        draw_rectangle(
+            offset_x,
+            offset_y,
+            width,
+            height,
+            Color::new(0., 0., 0., 0.6),
+        );
+        draw_centered_text(
+            "PAUSED",
+            screen_width() / 2.,
+            offset_y + height / 2.,
+            80.,
+            WHITE,
+        );
+
+        draw_centered_text("PAUSED", screen_width() / 2., offset_y - 80., 100., BLUE);
         draw_centered_text(
             "Press <q> to quit the game",
             screen_width() / 2.,
@@ -134,12 +437,155 @@ fn draw_board(board: &Board, snake: &Snake) {
             WHITE,
         );
         draw_centered_text(
-            "Use arrow keys or <wasd> for movement",
+            "Press <ESC> or <p> to play the game",
             screen_width() / 2.,
             offset_y - 20.,
             30.,
             WHITE,
         );
+    } else {
+        draw_centered_text("SNAKE", screen_width() / 2., offset_y - 80., 100., GREEN);
+        draw_centered_text(
+            "Press <q> to quit the game",
+            screen_width() / 2.,
+            offset_y - 60.,
+            30.,
+            WHITE,
+        );
+        draw_centered_text(
+            "Press <ESC> or <p> to pause the game",
+            screen_width() / 2.,
+            offset_y - 40.,
+            30.,
+            WHITE,
+        );
+        let movement = if game.players() > 1 {
+            "Player 1 uses <wasd>, player 2 uses arrow keys"
+        } else {
+            "Use arrow keys or <wasd> for movement"
+        };
+        draw_centered_text(movement, screen_width() / 2., offset_y - 20., 30., WHITE);
+    }
+}
+
+fn food_color(kind: FoodKind) -> Color {
+    match kind {
+        FoodKind::Normal => RED,
+        FoodKind::Bonus => SKYBLUE,
+        FoodKind::Golden => GOLD,
+        FoodKind::Poison => PURPLE,
+        FoodKind::Shrink => LIGHTGRAY,
+    }
+}
+
+fn player_colors(player: usize) -> (Color, Color) {
+    match player {
+        0 => (GREEN, BLUE),
+        _ => (ORANGE, MAROON),
+    }
+}
+
+fn draw_snake(
+    snake: &Snake,
+    player: usize,
+    interpolation: &Interpolation,
+    offset_x: f32,
+    offset_y: f32,
+) {
+    const BODY_INSET: f32 = BLOCK_SIZE * 0.15;
+    const TAIL_INSET: f32 = BLOCK_SIZE * 0.3;
+
+    let length = snake.len();
+    let (body, head) = player_colors(player);
+
+    for (i, segment) in snake.segments().enumerate() {
+        let (col, row) = interpolation.position(player, i, &segment);
+        let x = BLOCK_SIZE * col + offset_x;
+        let y = BLOCK_SIZE * row + offset_y;
+        let color = if i == 0 { head } else { body };
+        let inset = if i + 1 == length && i > 0 {
+            TAIL_INSET
+        } else {
+            BODY_INSET
+        };
+        let size = BLOCK_SIZE - inset * 2.;
+
+        draw_rectangle(x + inset, y + inset, size, size, color);
+
+        let behind = segment.incoming().map(Direction::opposite);
+        let ahead = if i == 0 { None } else { segment.outgoing() };
+        for direction in [behind, ahead].into_iter().flatten() {
+            match direction {
+                Direction::Up => draw_rectangle(x + inset, y, size, inset, color),
+                Direction::Down => {
+                    draw_rectangle(x + inset, y + BLOCK_SIZE - inset, size, inset, color)
+                }
+                Direction::Left => draw_rectangle(x, y + inset, inset, size, color),
+                Direction::Right => {
+                    draw_rectangle(x + BLOCK_SIZE - inset, y + inset, inset, size, color)
+                }
+            }
+        }
+
+        if i == 0 {
+            draw_eyes(x, y, *snake.direction());
+        }
+    }
+}
+
+fn draw_eyes(x: f32, y: f32, direction: Direction) {
+    const NEAR: f32 = BLOCK_SIZE * 0.3;
+    const FAR: f32 = BLOCK_SIZE * 0.7;
+    const RADIUS: f32 = BLOCK_SIZE * 0.08;
+
+    let eyes = match direction {
+        Direction::Up => [(NEAR, NEAR), (FAR, NEAR)],
+        Direction::Down => [(NEAR, FAR), (FAR, FAR)],
+        Direction::Left => [(NEAR, NEAR), (NEAR, FAR)],
+        Direction::Right => [(FAR, NEAR), (FAR, FAR)],
+    };
+
+    for (eye_x, eye_y) in eyes {
+        draw_circle(x + eye_x, y + eye_y, RADIUS, WHITE);
+    }
+}
+
+fn draw_border(board: &Board, x: f32, y: f32, width: f32, height: f32) {
+    const THICKNESS: f32 = 6.;
+    let edges = board.edges();
+
+    if edges.top_bottom == Edge::Wall {
+        draw_rectangle(
+            x - THICKNESS,
+            y - THICKNESS,
+            width + THICKNESS * 2.,
+            THICKNESS,
+            GRAY,
+        );
+        draw_rectangle(
+            x - THICKNESS,
+            y + height,
+            width + THICKNESS * 2.,
+            THICKNESS,
+            GRAY,
+        );
+    }
+
+    if edges.left_right == Edge::Wall {
+        draw_rectangle(
+            x - THICKNESS,
+            y - THICKNESS,
+            THICKNESS,
+            height + THICKNESS * 2.,
+            GRAY,
+        );
+        draw_rectangle(
+            x + width,
+            y - THICKNESS,
+            THICKNESS,
+            height + THICKNESS * 2.,
+            GRAY,
+        );
     }
 }
 
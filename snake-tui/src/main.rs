@@ -1,7 +1,3 @@
-use kira::{
-    manager::{backend::cpal::CpalBackend, AudioManager, AudioManagerSettings},
-    sound::static_sound::StaticSoundData,
-};
 use ruscii::{
     app::{App, Config, State},
     drawing::Pencil,
@@ -10,30 +6,58 @@ use ruscii::{
     spatial::Vec2,
     terminal::{Color, Window},
 };
-use snake::{Board, CellType, Direction, GameEvent, Snake};
-use std::{io::Cursor, sync::mpsc, time::Instant};
+use snake::{
+    ai::{Pilot, Seat},
+    CellType, Direction, Edge, FoodKind, Game,
+};
+use snake_audio::Audio;
+use snake_cli::{
+    breakdown_label, load_level, new_board, score_line, start_audio, start_game, steer, Args, Keys,
+};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
-const EAT_SOUND: &[u8] = include_bytes!("../../assets/eat.mp3");
-const DIE_SOUND: &[u8] = include_bytes!("../../assets/die.mp3");
-const MOVE_SOUND: &[u8] = include_bytes!("../../assets/move.mp3");
+const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
 
-struct Game {
-    board: Board,
-    snake: Snake,
+struct Tui {
+    game: Game,
     fps_counter: FPSCounter,
     offset: usize,
     center: usize,
     start: Instant,
+    last_step: Instant,
+    audio: Option<Audio>,
+    seats: Vec<Seat>,
+    show_path: bool,
 }
 
-impl Game {
+impl Tui {
+    fn press(&mut self, keys: Keys, direction: Direction) {
+        steer(&mut self.game, &self.seats, keys, direction);
+    }
+
+    fn volume_keybindings(&mut self, app_state: &mut State) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+        let volume = audio.volume();
+
+        for key_event in app_state.keyboard().last_key_events() {
+            match key_event {
+                KeyEvent::Pressed(Key::M) => volume.toggle_mute(),
+                KeyEvent::Pressed(Key::Equal) => volume.raise(),
+                KeyEvent::Pressed(Key::Minus) => volume.lower(),
+                _ => (),
+            }
+        }
+    }
+
     fn game_over_keybindings(&mut self, app_state: &mut State) {
         for key_event in app_state.keyboard().last_key_events() {
             match key_event {
-                KeyEvent::Pressed(Key::Space) => {
-                    self.board.reset();
-                    self.snake.reset();
-                }
+                KeyEvent::Pressed(Key::Space) => self.game.reset(),
                 KeyEvent::Pressed(Key::Q) => app_state.stop(),
                 _ => (),
             };
@@ -43,9 +67,7 @@ impl Game {
     fn paused_keybindings(&mut self, app_state: &mut State) {
         for key_event in app_state.keyboard().last_key_events() {
             match key_event {
-                KeyEvent::Pressed(Key::Esc) => {
-                    self.board.toggle_pause();
-                }
+                KeyEvent::Pressed(Key::Esc) => self.game.toggle_pause(),
                 KeyEvent::Pressed(Key::Q) => app_state.stop(),
                 _ => (),
             };
@@ -55,24 +77,18 @@ impl Game {
     fn game_keybindings(&mut self, app_state: &mut State) {
         for key_event in app_state.keyboard().last_key_events() {
             match key_event {
-                KeyEvent::Pressed(Key::Up) | KeyEvent::Pressed(Key::W) => {
-                    self.snake.change_direction(Direction::Up, &mut self.board);
-                }
-                KeyEvent::Pressed(Key::Down) | KeyEvent::Pressed(Key::S) => {
-                    self.snake
-                        .change_direction(Direction::Down, &mut self.board);
-                }
-                KeyEvent::Pressed(Key::Left) | KeyEvent::Pressed(Key::A) => {
-                    self.snake
-                        .change_direction(Direction::Left, &mut self.board);
-                }
-                KeyEvent::Pressed(Key::Right) | KeyEvent::Pressed(Key::D) => {
-                    self.snake
-                        .change_direction(Direction::Right, &mut self.board);
-                }
-                KeyEvent::Pressed(Key::Esc) => {
-                    self.board.toggle_pause();
-                }
+                KeyEvent::Pressed(Key::W) => self.press(Keys::Wasd, Direction::Up),
+                KeyEvent::Pressed(Key::S) => self.press(Keys::Wasd, Direction::Down),
+                KeyEvent::Pressed(Key::A) => self.press(Keys::Wasd, Direction::Left),
+                KeyEvent::Pressed(Key::D) => self.press(Keys::Wasd, Direction::Right),
+                KeyEvent::Pressed(Key::Up) => self.press(Keys::Arrows, Direction::Up),
+                KeyEvent::Pressed(Key::Down) => self.press(Keys::Arrows, Direction::Down),
+                KeyEvent::Pressed(Key::Left) => self.press(Keys::Arrows, Direction::Left),
+                KeyEvent::Pressed(Key::Right) => self.press(Keys::Arrows, Direction::Right),
+                KeyEvent::Pressed(Key::B) => self.seats[0].toggle(Pilot::Autopilot),
+                KeyEvent::Pressed(Key::C) => self.seats[0].toggle(Pilot::Solver),
+                KeyEvent::Pressed(Key::H) => self.show_path = !self.show_path,
+                KeyEvent::Pressed(Key::Esc) => self.game.toggle_pause(),
                 KeyEvent::Pressed(Key::Q) => app_state.stop(),
                 _ => (),
             }
@@ -80,23 +96,104 @@ impl Game {
     }
 
     fn draw_stats(&mut self, pencil: &mut Pencil) {
+        let score = self.game.score();
+        let breakdown = score.breakdown();
+        let label = breakdown_label(&self.game);
+        let (x, y) = (self.offset + 24, self.game.board().rows() + self.offset);
+
+        pencil.draw_text(&score_line(&self.game), Vec2::xy(self.offset, y + 1));
+        pencil.draw_text(
+            &format!(
+                "{label}Combo: x{} (best x{})",
+                score.multiplier(),
+                score.best_multiplier()
+            ),
+            Vec2::xy(x, y + 1),
+        );
+        pencil.draw_text(
+            &format!(
+                "{label}Food: {}  Combo: {}",
+                breakdown.food, breakdown.combo
+            ),
+            Vec2::xy(x, y + 2),
+        );
         pencil.draw_text(
-            &format!("Score: {}", self.snake.score()),
-            Vec2::xy(self.offset, self.board.rows() + self.offset + 1),
+            &format!(
+                "{label}Time: {}  Penalty: {}",
+                breakdown.time, breakdown.penalty
+            ),
+            Vec2::xy(x, y + 3),
+        );
+        pencil.draw_text(
+            &format!(
+                "Speed: {} ({})",
+                self.game.speed_level(),
+                self.game.difficulty().name()
+            ),
+            Vec2::xy(x, y + 4),
         );
         pencil.draw_text(
             &format!("FPS: {}", self.fps_counter.count()),
-            Vec2::xy(self.offset, self.board.rows() + self.offset + 2),
+            Vec2::xy(self.offset, y + 2),
         );
         pencil.draw_text(
             &format!("{} seconds", self.start.elapsed().as_secs()),
-            Vec2::xy(self.offset, self.board.rows() + self.offset + 3),
+            Vec2::xy(self.offset, y + 3),
+        );
+        if let Some(seed) = self.game.board().seed() {
+            pencil.draw_text(&format!("Seed: {seed}"), Vec2::xy(self.offset, y + 4));
+        }
+
+        let x = x + 28;
+        let volume = match self.audio.as_ref().map(Audio::volume) {
+            None => "off".to_owned(),
+            Some(volume) if volume.muted() => "muted".to_owned(),
+            Some(volume) => format!("{}%", volume.level()),
+        };
+        pencil.draw_text(&format!("Volume: {volume}"), Vec2::xy(x, y + 1));
+        pencil.draw_text("Press <m>, <-> or <+>", Vec2::xy(x, y + 2));
+        let seat = &self.seats[0];
+        pencil.draw_text(
+            &format!("Player 1: {} (<b>, <c>)", seat.pilot().name()),
+            Vec2::xy(x, y + 3),
         );
+        let path = match (seat.solver(), self.show_path) {
+            (None, _) => "Solver: unavailable",
+            (Some(_), true) => "Path: on (press <h>)",
+            (Some(_), false) => "Path: off (press <h>)",
+        };
+        pencil.draw_text(path, Vec2::xy(x, y + 4));
     }
 
     fn draw_game_over_header(&mut self, pencil: &mut Pencil) {
-        pencil.set_foreground(Color::Red);
-        pencil.draw_center_text("GAME OVER", Vec2::xy(self.center, 1));
+        match (self.game.players(), self.game.winner()) {
+            (1, _) => {
+                pencil.set_foreground(Color::Red);
+                pencil.draw_center_text("GAME OVER", Vec2::xy(self.center, 1));
+            }
+            (_, Some(player)) => {
+                pencil.set_foreground(player_colors(player).0);
+                pencil.draw_center_text(
+                    &format!("PLAYER {} WINS", player + 1),
+                    Vec2::xy(self.center, 1),
+                );
+            }
+            (_, None) => {
+                pencil.set_foreground(Color::Red);
+                pencil.draw_center_text("DRAW", Vec2::xy(self.center, 1));
+            }
+        }
+        pencil.set_foreground(Color::White);
+        pencil.draw_center_text(
+            "Press <space> to restart the game",
+            Vec2::xy(self.center, 2),
+        );
+        pencil.draw_center_text("Press <q> to quit the game", Vec2::xy(self.center, 3));
+    }
+
+    fn draw_victory_header(&mut self, pencil: &mut Pencil) {
+        pencil.set_foreground(Color::Yellow);
+        pencil.draw_center_text("BOARD CLEARED", Vec2::xy(self.center, 1));
         pencil.set_foreground(Color::White);
         pencil.draw_center_text(
             "Press <space> to restart the game",
@@ -111,10 +208,12 @@ impl Game {
         pencil.set_foreground(Color::White);
         pencil.draw_center_text("Press <q> to quit the game", Vec2::xy(self.center, 2));
         pencil.draw_center_text("Press <ESC> to pause the game", Vec2::xy(self.center, 3));
-        pencil.draw_center_text(
-            "Use arrow keys or <wasd> for movement",
-            Vec2::xy(self.center, 4),
-        );
+        let movement = if self.game.players() > 1 {
+            "Player 1 uses <wasd>, player 2 uses arrow keys"
+        } else {
+            "Use arrow keys or <wasd> for movement"
+        };
+        pencil.draw_center_text(movement, Vec2::xy(self.center, 4));
     }
 
     fn draw_paused_header(&mut self, pencil: &mut Pencil) {
@@ -125,110 +224,170 @@ impl Game {
         pencil.draw_center_text("Press <ESC> to play the game", Vec2::xy(self.center, 3));
     }
 
+    fn draw_border(&mut self, pencil: &mut Pencil) {
+        let board = self.game.board();
+        let edges = board.edges();
+        let (left, top) = (self.offset - 1, self.offset - 1);
+        let (right, bottom) = (self.offset + board.columns(), self.offset + board.rows());
+
+        pencil.set_background(Color::Black);
+        pencil.set_foreground(Color::White);
+
+        if edges.top_bottom == Edge::Wall {
+            for x in left..=right {
+                pencil.draw_char('█', Vec2::xy(x, top));
+                pencil.draw_char('█', Vec2::xy(x, bottom));
+            }
+        }
+
+        if edges.left_right == Edge::Wall {
+            for y in top..=bottom {
+                pencil.draw_char('█', Vec2::xy(left, y));
+                pencil.draw_char('█', Vec2::xy(right, y));
+            }
+        }
+    }
+
     fn draw_board(&mut self, pencil: &mut Pencil) {
-        for cell in self.board.cells() {
+        let mut path = HashSet::new();
+        for (player, seat) in self.seats.iter().enumerate() {
+            if let (Pilot::Solver, Some(solver)) = (seat.pilot(), seat.solver()) {
+                if self.show_path && self.game.on_board(player) {
+                    let snake = &self.game.snakes()[player];
+                    path.extend(solver.path(self.game.board(), snake));
+                }
+            }
+        }
+
+        for cell in self.game.board().cells() {
+            let on_path = path.contains(&(cell.col(), cell.row()));
             let c = match cell.cell_type() {
+                CellType::Empty if on_path => '•',
                 CellType::Empty => '·',
-                CellType::Food => ' ',
-                CellType::Snake => ' ',
-                CellType::SnakeHead => ' ',
+                CellType::Food(_) => ' ',
+                CellType::Snake(_) => ' ',
+                CellType::SnakeHead(_) => ' ',
+                CellType::Wall => ' ',
             };
             let p = Vec2::xy(cell.col() + self.offset, cell.row() + self.offset);
 
             match cell.cell_type() {
-                CellType::Snake => {
-                    pencil.set_background(Color::Green);
+                CellType::Snake(id) => {
+                    pencil.set_background(player_colors(id.index()).0);
                 }
-                CellType::SnakeHead => {
-                    pencil.set_background(Color::Blue);
+                CellType::SnakeHead(id) => {
+                    pencil.set_background(player_colors(id.index()).1);
                 }
-                CellType::Food => {
-                    pencil.set_background(Color::Red);
+                CellType::Food(kind) => {
+                    pencil.set_background(food_color(kind));
                 }
                 CellType::Empty => {
                     pencil.set_background(Color::Black);
                 }
+                CellType::Wall => {
+                    pencil.set_background(Color::Grey);
+                }
             };
 
+            pencil.set_foreground(if on_path { Color::Yellow } else { Color::White });
             pencil.draw_char(c, p);
         }
+        pencil.set_foreground(Color::White);
+    }
+}
+
+fn player_colors(player: usize) -> (Color, Color) {
+    match player {
+        0 => (Color::Green, Color::Blue),
+        _ => (Color::Xterm(214), Color::Xterm(202)),
     }
 }
 
-fn make_sound_data(sound: &'static [u8]) -> StaticSoundData {
-    let sound_data_cursor = Cursor::new(sound);
-    StaticSoundData::from_cursor(sound_data_cursor).unwrap()
+fn food_color(kind: FoodKind) -> Color {
+    match kind {
+        FoodKind::Normal => Color::Red,
+        FoodKind::Bonus => Color::Cyan,
+        FoodKind::Golden => Color::Yellow,
+        FoodKind::Poison => Color::Magenta,
+        FoodKind::Shrink => Color::LightGrey,
+    }
 }
 
 fn main() {
-    let (tx, rx) = mpsc::sync_channel(1);
-    let mut manager = AudioManager::<CpalBackend>::new(AudioManagerSettings::default())
-        .expect("Failed to create audio manager");
-
-    let eat_sound = make_sound_data(EAT_SOUND);
-    let die_sound = make_sound_data(DIE_SOUND);
-    let move_sound = make_sound_data(MOVE_SOUND);
-
-    std::thread::spawn(move || {
-        while let Ok(msg) = rx.recv() {
-            match msg {
-                GameEvent::FoodEaten => {
-                    let _ = manager.play(eat_sound.clone());
-                }
-                GameEvent::SnakeDied => {
-                    let _ = manager.play(die_sound.clone());
-                }
-                GameEvent::SnakeChangedDirection => {
-                    let _ = manager.play(move_sound.clone());
-                }
-            }
-        }
-    });
+    let args = Args::parse("snake-tui");
+    let audio = start_audio(&args);
 
-    let config = Config::new().fps(15);
+    let config = Config::new().fps(60);
     let mut app = App::config(config);
 
-    let offset = 5;
+    let level = args.level.as_deref().map(|path| load_level(path, &args));
+    let edges = args
+        .edges
+        .or(level.as_ref().map(|(board, _)| board.edges()));
+
+    let offset = if edges.is_some_and(|edges| edges.has_walls()) {
+        6
+    } else {
+        5
+    };
     let size = app.window().size();
     let cols = size.x.try_into().unwrap_or(15);
     let rows = size.y.try_into().unwrap_or(10);
 
-    let snake = Snake::new();
-    let mut board = Board::new(rows - (offset * 2), cols - (offset * 2), Some(tx));
-
-    board.generate_food();
+    let (board, snakes) = match level {
+        Some((board, snake)) => (board, vec![snake]),
+        None => new_board(&args, rows - (offset * 2), cols - (offset * 2)),
+    };
+    let (game, seats) = start_game(&args, board, snakes, audio.as_ref());
 
     let fps_counter = FPSCounter::default();
-    let center = (board.columns() / 2) + offset;
+    let center = (game.board().columns() / 2) + offset;
 
-    let mut game = Game {
-        board,
-        snake,
+    let mut tui = Tui {
+        game,
         fps_counter,
         offset,
         center,
         start: Instant::now(),
+        last_step: Instant::now(),
+        audio,
+        seats,
+        show_path: false,
     };
 
     app.run(|app_state: &mut State, window: &mut Window| {
-        game.fps_counter.update();
+        tui.fps_counter.update();
         let mut pencil = Pencil::new(window.canvas_mut());
 
-        if game.board.game_over() {
-            game.draw_game_over_header(&mut pencil);
-            game.game_over_keybindings(app_state);
-        } else if game.board.paused() {
-            game.draw_paused_header(&mut pencil);
-            game.paused_keybindings(app_state);
+        tui.volume_keybindings(app_state);
+        if tui.game.victory() {
+            tui.draw_victory_header(&mut pencil);
+            tui.game_over_keybindings(app_state);
+        } else if tui.game.game_over() {
+            tui.draw_game_over_header(&mut pencil);
+            tui.game_over_keybindings(app_state);
+        } else if tui.game.paused() {
+            tui.draw_paused_header(&mut pencil);
+            tui.paused_keybindings(app_state);
         } else {
-            game.draw_game_header(&mut pencil);
-            game.game_keybindings(app_state);
-
-            game.snake.update(&mut game.board);
-            game.snake.update_movement(&mut game.board);
+            tui.draw_game_header(&mut pencil);
+            tui.game_keybindings(app_state);
+            // ruscii sleeps to a fixed frame rate, so catch up on every step
+            // that fell due since the last frame instead of rounding each one up.
+            if tui.last_step.elapsed() > MAX_FRAME_TIME {
+                tui.last_step = Instant::now() - tui.game.tick_interval();
+            }
+            while !tui.game.game_over() && tui.last_step.elapsed() >= tui.game.tick_interval() {
+                tui.last_step += tui.game.tick_interval();
+                tui.game.step_with(&mut tui.seats);
+            }
+        }
+        if tui.game.game_over() || tui.game.paused() {
+            tui.last_step = Instant::now();
         }
 
-        game.draw_stats(&mut pencil);
-        game.draw_board(&mut pencil);
+        tui.draw_stats(&mut pencil);
+        tui.draw_border(&mut pencil);
+        tui.draw_board(&mut pencil);
     });
 }
@@ -0,0 +1,42 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use snake::{Board, Direction, Game, Snake, SnakeConfig};
+
+const SIZES: [usize; 4] = [20, 100, 500, 1000];
+
+fn new_game(size: usize) -> Game {
+    let board = Board::with_seed(size, size, 42);
+    let snake = Snake::new(SnakeConfig::default(), &board).unwrap();
+    Game::new(board, snake)
+}
+
+fn tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick");
+    group.throughput(Throughput::Elements(1));
+
+    for size in SIZES {
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, &size| {
+            let mut game = new_game(size);
+            let mut ticks = 0;
+
+            b.iter(|| {
+                // Sweep the torus row by row so the snake keeps eating without biting itself.
+                let input = match ticks % size {
+                    0 => Some(Direction::Down),
+                    1 => Some(Direction::Right),
+                    _ => None,
+                };
+                ticks += 1;
+
+                game.step(input);
+                if game.game_over() {
+                    game.reset();
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, tick);
+criterion_main!(benches);
@@ -0,0 +1,175 @@
+use std::collections::VecDeque;
+
+mod hamiltonian;
+mod seat;
+
+pub use hamiltonian::*;
+pub use seat::*;
+
+use super::{Board, CellType, Controller, Direction, GameView};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Left,
+    Direction::Down,
+    Direction::Right,
+];
+
+/// Steers a snake along the shortest path to the nearest food that makes it
+/// grow, found with a breadth-first search that follows the board's edges.
+/// Food that shrinks the snake is avoided unless nothing else is reachable.
+///
+/// A move towards food is only taken when the snake still has at least its own
+/// length of free cells to roam afterwards. Otherwise, or when no food can be
+/// reached, it heads for the neighbouring cell with the most room.
+#[derive(Default)]
+pub struct Autopilot {
+    first_steps: Vec<Option<Direction>>,
+    visited: Vec<bool>,
+    queue: VecDeque<(usize, usize)>,
+}
+
+impl Autopilot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn step_towards(
+        &mut self,
+        board: &Board,
+        col: usize,
+        row: usize,
+        target: Target,
+    ) -> Option<Direction> {
+        self.first_steps.clear();
+        self.first_steps.resize(board.cells().len(), None);
+        self.queue.clear();
+
+        for direction in DIRECTIONS {
+            self.visit(board, col, row, direction, direction, target);
+        }
+
+        while let Some((col, row)) = self.queue.pop_front() {
+            let first_step = self.first_steps[index(board, col, row)];
+            if target.matches(board, col, row) {
+                return first_step;
+            }
+
+            for direction in DIRECTIONS {
+                self.visit(board, col, row, direction, first_step.unwrap(), target);
+            }
+        }
+
+        None
+    }
+
+    fn visit(
+        &mut self,
+        board: &Board,
+        col: usize,
+        row: usize,
+        direction: Direction,
+        first_step: Direction,
+        target: Target,
+    ) {
+        let Some((col, row)) = board.neighbor(col, row, direction) else {
+            return;
+        };
+
+        let index = index(board, col, row);
+        if self.first_steps[index].is_none() && target.passable(board, col, row) {
+            self.first_steps[index] = Some(first_step);
+            self.queue.push_back((col, row));
+        }
+    }
+
+    /// Counts the free cells reachable from a cell, stopping once `limit` is hit.
+    fn room(&mut self, board: &Board, col: usize, row: usize, limit: usize) -> usize {
+        self.visited.clear();
+        self.visited.resize(board.cells().len(), false);
+        self.queue.clear();
+
+        self.visited[index(board, col, row)] = true;
+        self.queue.push_back((col, row));
+        let mut room = 0;
+
+        while let Some((col, row)) = self.queue.pop_front() {
+            room += 1;
+            if room >= limit {
+                break;
+            }
+
+            for direction in DIRECTIONS {
+                let Some((col, row)) = board.neighbor(col, row, direction) else {
+                    continue;
+                };
+
+                let index = index(board, col, row);
+                if !self.visited[index] && is_free(board, col, row) {
+                    self.visited[index] = true;
+                    self.queue.push_back((col, row));
+                }
+            }
+        }
+
+        room
+    }
+}
+
+impl Controller for Autopilot {
+    fn decide(&mut self, view: &GameView) -> Option<Direction> {
+        let (board, snake) = (view.board(), view.snake());
+        let (col, row) = snake.head();
+
+        let step = self
+            .step_towards(board, col, row, Target::Growth)
+            .or_else(|| self.step_towards(board, col, row, Target::Any));
+        if let Some(direction) = step {
+            let (col, row) = board.neighbor(col, row, direction)?;
+            if self.room(board, col, row, snake.len()) >= snake.len() {
+                return Some(direction);
+            }
+        }
+
+        DIRECTIONS
+            .into_iter()
+            .filter_map(|direction| {
+                let (col, row) = board.neighbor(col, row, direction)?;
+                is_free(board, col, row)
+                    .then(|| (direction, self.room(board, col, row, board.cells().len())))
+            })
+            .max_by_key(|&(_, room)| room)
+            .map(|(direction, _)| direction)
+    }
+}
+
+fn index(board: &Board, col: usize, row: usize) -> usize {
+    col + row * board.columns()
+}
+
+fn is_free(board: &Board, col: usize, row: usize) -> bool {
+    !board.is_occupied(col, row) && !board.is_wall(col, row)
+}
+
+#[derive(Copy, Clone)]
+enum Target {
+    Growth,
+    Any,
+}
+
+impl Target {
+    fn matches(self, board: &Board, col: usize, row: usize) -> bool {
+        match (self, board.get_cell(col, row)) {
+            (Target::Growth, CellType::Food(kind)) => kind.growth() > 0,
+            (Target::Any, CellType::Food(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn passable(self, board: &Board, col: usize, row: usize) -> bool {
+        match (self, board.get_cell(col, row)) {
+            (Target::Growth, CellType::Food(kind)) => kind.shrink() == 0,
+            _ => is_free(board, col, row),
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use super::{index, is_free, DIRECTIONS};
+use crate::{Board, CellType, Controller, Direction, GameView, Snake};
+
+/// Free cells kept between the head and the tail after a shortcut on top of
+/// the growth from food ahead, enough for a snake still digesting a bonus.
+const SHORTCUT_MARGIN: usize = 5;
+
+/// Follows a Hamiltonian cycle through every cell of the board, which fills the
+/// whole board without ever trapping the snake. While the snake covers less
+/// than half of the board it cuts across the cycle towards food, never
+/// jumping past the food or closer than a few cells to its own tail.
+///
+/// Cycles exist for boards without walls that are at least two cells wide and
+/// tall with an even number of rows or columns. The solver relies on the body
+/// lying along the cycle, so it plays perfectly from the start of a game and
+/// does its best when handed a snake mid-game.
+pub struct Hamiltonian {
+    cycle: Vec<(usize, usize)>,
+    order: Vec<usize>,
+}
+
+impl Hamiltonian {
+    pub fn new(board: &Board) -> Option<Self> {
+        let (rows, columns) = (board.rows(), board.columns());
+        if rows < 2 || columns < 2 || (rows % 2 == 1 && columns % 2 == 1) {
+            return None;
+        }
+        if board
+            .cells()
+            .iter()
+            .any(|cell| cell.cell_type() == CellType::Wall)
+        {
+            return None;
+        }
+
+        // Runs out along the first row, zigzags back over the remaining rows
+        // and returns up the first column. With an odd number of rows the same
+        // layout is built over the columns instead.
+        let transposed = rows % 2 == 1;
+        let (rows, columns) = if transposed {
+            (columns, rows)
+        } else {
+            (rows, columns)
+        };
+
+        let mut cycle = Vec::with_capacity(rows * columns);
+        cycle.extend((0..columns).map(|col| (col, 0)));
+        for row in 1..rows {
+            if row % 2 == 1 {
+                cycle.extend((1..columns).rev().map(|col| (col, row)));
+            } else {
+                cycle.extend((1..columns).map(|col| (col, row)));
+            }
+        }
+        cycle.extend((1..rows).rev().map(|row| (0, row)));
+
+        if transposed {
+            for cell in &mut cycle {
+                *cell = (cell.1, cell.0);
+            }
+        }
+
+        let mut order = vec![0; cycle.len()];
+        for (position, &(col, row)) in cycle.iter().enumerate() {
+            order[index(board, col, row)] = position;
+        }
+
+        Some(Self { cycle, order })
+    }
+
+    /// Every cell of the board in the order the cycle visits them.
+    pub fn cycle(&self) -> &[(usize, usize)] {
+        &self.cycle
+    }
+
+    /// The cells ahead of the head along the cycle, up to the tail.
+    pub fn path<'a>(
+        &'a self,
+        board: &Board,
+        snake: &Snake,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let (col, row) = snake.head();
+        let head = self.position(board, col, row);
+        let (col, row) = snake.tail();
+        let ahead = self.distance(head, self.position(board, col, row));
+
+        (1..ahead).map(move |step| self.cycle[(head + step) % self.cycle.len()])
+    }
+
+    fn position(&self, board: &Board, col: usize, row: usize) -> usize {
+        self.order[index(board, col, row)]
+    }
+
+    fn distance(&self, from: usize, to: usize) -> usize {
+        (to + self.cycle.len() - from) % self.cycle.len()
+    }
+}
+
+impl Controller for Hamiltonian {
+    fn decide(&mut self, view: &GameView) -> Option<Direction> {
+        let (board, snake) = (view.board(), view.snake());
+        let (col, row) = snake.head();
+        let head = self.position(board, col, row);
+        let (tail_col, tail_row) = snake.tail();
+        let to_tail = self.distance(head, self.position(board, tail_col, tail_row));
+
+        // Food that shrinks the snake never expires, so it is only worth eating
+        // once nothing else is left to clear it off the board.
+        let food_distance = |growth: bool| {
+            board
+                .food()
+                .iter()
+                .filter(|food| !growth || food.kind().growth() > 0)
+                .map(|food| self.distance(head, self.position(board, food.col(), food.row())))
+                .min()
+        };
+        let to_food = food_distance(true)
+            .or_else(|| food_distance(false))
+            .unwrap_or(to_tail);
+
+        // The tail stays put while the snake grows, so every bite taken before
+        // the head reaches the tail eats into the room a shortcut leaves.
+        let growth: usize = board
+            .food()
+            .iter()
+            .filter(|food| {
+                self.distance(head, self.position(board, food.col(), food.row())) < to_tail
+            })
+            .map(|food| food.kind().growth())
+            .sum();
+
+        // Cells skipped by a shortcut only free up once the tail passes them,
+        // so keep at least half of the free cells in front of the head.
+        let free = self.cycle.len().saturating_sub(snake.len());
+        let reserve = (SHORTCUT_MARGIN + growth).max(free / 2);
+
+        let shortcut = if snake.len() * 2 < self.cycle.len() {
+            to_tail.saturating_sub(reserve).min(to_food).max(1)
+        } else {
+            1
+        };
+
+        let moves: Vec<(Direction, usize)> = DIRECTIONS
+            .into_iter()
+            .filter_map(|direction| {
+                let (col, row) = board.neighbor(col, row, direction)?;
+                is_free(board, col, row).then(|| {
+                    (
+                        direction,
+                        self.distance(head, self.position(board, col, row)),
+                    )
+                })
+            })
+            .collect();
+
+        // Off the cycle, stay as close to it as possible until the body lines
+        // up again.
+        moves
+            .iter()
+            .filter(|&&(_, distance)| distance <= shortcut)
+            .max_by_key(|&&(_, distance)| distance)
+            .or_else(|| moves.iter().min_by_key(|&&(_, distance)| distance))
+            .map(|&(direction, _)| direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Game, SnakeConfig, StepOutcome};
+
+    fn play(rows: usize, columns: usize, special_food: bool) -> StepOutcome {
+        let mut board = Board::with_seed(rows, columns, 3);
+        board.set_special_food(special_food);
+        let snake = Snake::new(SnakeConfig::default(), &board).unwrap();
+        let mut controllers = [Hamiltonian::new(&board).unwrap()];
+        let mut game = Game::new(board, snake);
+
+        let mut outcome = StepOutcome::Moved;
+        for _ in 0..100 * rows * columns {
+            outcome = game.step_with(&mut controllers);
+            if game.game_over() {
+                break;
+            }
+        }
+        outcome
+    }
+
+    #[test]
+    fn fills_the_board() {
+        // 9x8 lays the cycle out over the columns.
+        for (rows, columns) in [(6, 6), (8, 9), (9, 8), (10, 10)] {
+            for special_food in [false, true] {
+                assert_eq!(
+                    play(rows, columns, special_food),
+                    StepOutcome::Victory,
+                    "{rows}x{columns}, special food {special_food}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cycle_visits_every_cell_once() {
+        for (rows, columns) in [(2, 2), (6, 7), (7, 6)] {
+            let board = Board::new(rows, columns);
+            let cycle = Hamiltonian::new(&board).unwrap().cycle().to_vec();
+
+            let mut cells = cycle.clone();
+            cells.sort_unstable();
+            cells.dedup();
+            assert_eq!(cells.len(), rows * columns);
+
+            for (index, &(col, row)) in cycle.iter().enumerate() {
+                let (next_col, next_row) = cycle[(index + 1) % cycle.len()];
+                assert_eq!(col.abs_diff(next_col) + row.abs_diff(next_row), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn no_cycle_on_odd_boards_or_walls() {
+        assert!(Hamiltonian::new(&Board::new(5, 7)).is_none());
+
+        let mut board = Board::new(6, 6);
+        board.set_layout_cell(2, 2, CellType::Wall);
+        assert!(Hamiltonian::new(&board).is_none());
+    }
+}
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use super::{Autopilot, Hamiltonian};
+use crate::{Board, Controller, Direction, GameView};
+
+/// Who steers a [`Seat`].
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Pilot {
+    #[default]
+    Human,
+    Autopilot,
+    Solver,
+}
+
+impl Pilot {
+    pub fn name(self) -> &'static str {
+        match self {
+            Pilot::Human => "human",
+            Pilot::Autopilot => "autopilot",
+            Pilot::Solver => "solver",
+        }
+    }
+}
+
+impl FromStr for Pilot {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Pilot::Human),
+            "autopilot" => Ok(Pilot::Autopilot),
+            "solver" => Ok(Pilot::Solver),
+            _ => Err(format!(
+                "unknown player `{s}`, expected human, autopilot or solver"
+            )),
+        }
+    }
+}
+
+/// One player's snake, steered by the keyboard or handed to a bot mid-game.
+pub struct Seat {
+    pilot: Pilot,
+    autopilot: Autopilot,
+    solver: Option<Hamiltonian>,
+}
+
+impl Seat {
+    pub fn new(board: &Board) -> Self {
+        Self {
+            pilot: Pilot::Human,
+            autopilot: Autopilot::new(),
+            solver: Hamiltonian::new(board),
+        }
+    }
+
+    pub fn pilot(&self) -> Pilot {
+        self.pilot
+    }
+
+    pub fn is_human(&self) -> bool {
+        self.pilot == Pilot::Human
+    }
+
+    /// Hands the snake to `pilot`. Returns `false` and keeps the current pilot
+    /// when the solver is asked for on a board without a Hamiltonian cycle.
+    pub fn set_pilot(&mut self, pilot: Pilot) -> bool {
+        if pilot == Pilot::Solver && self.solver.is_none() {
+            return false;
+        }
+        self.pilot = pilot;
+        true
+    }
+
+    /// Hands the snake to `pilot`, or back to the keyboard if it already has it.
+    pub fn toggle(&mut self, pilot: Pilot) {
+        if self.pilot == pilot {
+            self.pilot = Pilot::Human;
+        } else {
+            self.set_pilot(pilot);
+        }
+    }
+
+    /// The solver for this board, `None` when the board has no cycle.
+    pub fn solver(&self) -> Option<&Hamiltonian> {
+        self.solver.as_ref()
+    }
+}
+
+impl Controller for Seat {
+    fn decide(&mut self, view: &GameView) -> Option<Direction> {
+        match (self.pilot, &mut self.solver) {
+            (Pilot::Autopilot, _) => self.autopilot.decide(view),
+            (Pilot::Solver, Some(solver)) => solver.decide(view),
+            // Key presses go straight to `Game::turn_player`, which queues
+            // them on the snake, so a human seat has nothing to decide.
+            _ => None,
+        }
+    }
+}
@@ -1,13 +1,19 @@
-use std::{fmt::Debug, sync::mpsc::SyncSender};
+use std::{fmt::Debug, str::FromStr};
 
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, thread_rng, Rng, RngCore, SeedableRng};
+
+use super::{Direction, Food, FoodKind, GameEvent, GameObserver, SnakeId};
+
+const FOOD_ATTEMPTS: usize = 32;
+const DEFAULT_MAX_FOOD: usize = 64;
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum CellType {
     Empty,
-    Snake,
-    Food,
-    SnakeHead,
+    Snake(SnakeId),
+    Food(FoodKind),
+    SnakeHead(SnakeId),
+    Wall,
 }
 
 #[derive(Debug)]
@@ -31,23 +37,98 @@ impl Cell {
     }
 }
 
-pub enum GameEvent {
-    FoodEaten,
-    SnakeDied,
-    SnakeChangedDirection,
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Edge {
+    Wrap,
+    Wall,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Edges {
+    pub left_right: Edge,
+    pub top_bottom: Edge,
+}
+
+impl Edges {
+    pub fn wrap() -> Self {
+        Self {
+            left_right: Edge::Wrap,
+            top_bottom: Edge::Wrap,
+        }
+    }
+
+    pub fn walls() -> Self {
+        Self {
+            left_right: Edge::Wall,
+            top_bottom: Edge::Wall,
+        }
+    }
+
+    pub fn has_walls(&self) -> bool {
+        self.left_right == Edge::Wall || self.top_bottom == Edge::Wall
+    }
+}
+
+impl Default for Edges {
+    fn default() -> Self {
+        Self::wrap()
+    }
+}
+
+impl FromStr for Edges {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wrap" => Ok(Self::wrap()),
+            "walls" => Ok(Self::walls()),
+            "left-right" => Ok(Self {
+                left_right: Edge::Wall,
+                top_bottom: Edge::Wrap,
+            }),
+            "top-bottom" => Ok(Self {
+                left_right: Edge::Wrap,
+                top_bottom: Edge::Wall,
+            }),
+            _ => Err(format!(
+                "unknown edges `{s}`, expected wrap, walls, left-right or top-bottom"
+            )),
+        }
+    }
 }
 
 pub struct Board {
     rows: usize,
     columns: usize,
     cells: Vec<Cell>,
+    layout: Vec<CellType>,
+    occupied: Vec<u64>,
+    food: Vec<Food>,
+    food_count: usize,
+    max_food: usize,
+    special_food: bool,
+    ticks: u64,
     game_over: bool,
+    victory: bool,
     paused: bool,
-    event_sender: Option<SyncSender<GameEvent>>,
+    edges: Edges,
+    observers: Vec<Box<dyn GameObserver + Send>>,
+    seed: Option<u64>,
+    rng: Box<dyn RngCore + Send>,
 }
 
 impl Board {
-    pub fn new(rows: usize, columns: usize, event_sender: Option<SyncSender<GameEvent>>) -> Self {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self::with_seed(rows, columns, thread_rng().gen())
+    }
+
+    pub fn with_seed(rows: usize, columns: usize, seed: u64) -> Self {
+        let mut board = Self::with_rng(rows, columns, StdRng::seed_from_u64(seed));
+        board.seed = Some(seed);
+        board
+    }
+
+    pub fn with_rng(rows: usize, columns: usize, rng: impl RngCore + Send + 'static) -> Self {
         let mut cells = Vec::with_capacity(rows * columns);
 
         for row in 0..rows {
@@ -64,9 +145,55 @@ impl Board {
             rows,
             columns,
             cells,
+            layout: vec![CellType::Empty; rows * columns],
+            occupied: vec![0; (rows * columns).div_ceil(64)],
+            food: Vec::new(),
+            food_count: 1,
+            max_food: DEFAULT_MAX_FOOD,
+            special_food: true,
+            ticks: 0,
             game_over: false,
+            victory: false,
             paused: false,
-            event_sender,
+            edges: Edges::default(),
+            observers: Vec::new(),
+            seed: None,
+            rng: Box::new(rng),
+        }
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.rng = Box::new(StdRng::seed_from_u64(seed));
+    }
+
+    pub fn edges(&self) -> Edges {
+        self.edges
+    }
+
+    pub fn set_edges(&mut self, edges: Edges) {
+        self.edges = edges;
+    }
+
+    pub fn neighbor(&self, col: usize, row: usize, direction: Direction) -> Option<(usize, usize)> {
+        let wraps = match direction {
+            Direction::Up | Direction::Down => self.edges.top_bottom == Edge::Wrap,
+            Direction::Left | Direction::Right => self.edges.left_right == Edge::Wrap,
+        };
+
+        match direction {
+            Direction::Up if row == 0 => wraps.then_some((col, self.rows - 1)),
+            Direction::Up => Some((col, row - 1)),
+            Direction::Down if row + 1 == self.rows => wraps.then_some((col, 0)),
+            Direction::Down => Some((col, row + 1)),
+            Direction::Left if col == 0 => wraps.then_some((self.columns - 1, row)),
+            Direction::Left => Some((col - 1, row)),
+            Direction::Right if col + 1 == self.columns => wraps.then_some((0, row)),
+            Direction::Right => Some((col + 1, row)),
         }
     }
 
@@ -82,10 +209,17 @@ impl Board {
         self.game_over
     }
 
-    pub fn end_game(&mut self) {
-        if let Some(event_sender) = &self.event_sender {
-            let _ = event_sender.send(GameEvent::SnakeDied);
-        }
+    pub(crate) fn finish(&mut self) {
+        self.game_over = true;
+    }
+
+    pub fn victory(&self) -> bool {
+        self.victory
+    }
+
+    pub(crate) fn win_game(&mut self) {
+        self.emit(GameEvent::BoardCleared);
+        self.victory = true;
         self.game_over = true;
     }
 
@@ -101,18 +235,56 @@ impl Board {
         &self.cells
     }
 
-    pub fn set_cell(&mut self, col: usize, row: usize, cell: CellType) {
-        let index = (col + row * self.columns) % self.cells.len();
+    fn index(&self, col: usize, row: usize) -> usize {
+        (col + row * self.columns) % self.cells.len()
+    }
+
+    fn fill(&mut self, index: usize, cell: CellType) {
+        let bit = 1 << (index % 64);
+        match cell {
+            CellType::Snake(_) | CellType::SnakeHead(_) => self.occupied[index / 64] |= bit,
+            _ => self.occupied[index / 64] &= !bit,
+        }
+
+        let (col, row) = (self.cells[index].col, self.cells[index].row);
+        if let CellType::Food(_) = self.cells[index].cell {
+            self.food.retain(|food| food.col != col || food.row != row);
+        }
+        if let CellType::Food(kind) = cell {
+            self.food.push(Food {
+                col,
+                row,
+                kind,
+                spawned_at: self.ticks,
+                expires_at: kind.lifetime().map(|lifetime| self.ticks + lifetime),
+            });
+        }
+
         self.cells[index].cell = cell;
     }
 
+    pub fn set_cell(&mut self, col: usize, row: usize, cell: CellType) {
+        let index = self.index(col, row);
+        self.fill(index, cell);
+    }
+
+    pub fn set_layout_cell(&mut self, col: usize, row: usize, cell: CellType) {
+        let index = self.index(col, row);
+        self.layout[index] = cell;
+        self.fill(index, cell);
+    }
+
     pub fn get_cell(&self, col: usize, row: usize) -> CellType {
-        let index = (col + row * self.columns) % self.cells.len();
-        self.cells[index].cell
+        self.cells[self.index(col, row)].cell
+    }
+
+    pub fn is_occupied(&self, col: usize, row: usize) -> bool {
+        let index = self.index(col, row);
+        self.occupied[index / 64] & (1 << (index % 64)) != 0
     }
 
     pub fn is_food(&self, col: usize, row: usize) -> bool {
-        self.get_cell(col, row) == CellType::Food
+        matches!(self.get_cell(col, row), CellType::Food(_))
     }
 
     pub fn is_empty(&self, col: usize, row: usize) -> bool {
@@ -120,39 +292,223 @@ impl Board {
     }
 
     pub fn is_snake(&self, col: usize, row: usize) -> bool {
-        self.get_cell(col, row) == CellType::Snake
+        matches!(self.get_cell(col, row), CellType::Snake(_))
+    }
+
+    pub fn snake_at(&self, col: usize, row: usize) -> Option<SnakeId> {
+        match self.get_cell(col, row) {
+            CellType::Snake(id) | CellType::SnakeHead(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    pub fn is_wall(&self, col: usize, row: usize) -> bool {
+        self.get_cell(col, row) == CellType::Wall
+    }
+
+    pub fn has_food(&self) -> bool {
+        !self.food.is_empty()
+    }
+
+    pub fn food(&self) -> &[Food] {
+        &self.food
+    }
+
+    pub fn food_at(&self, col: usize, row: usize) -> Option<&Food> {
+        self.food
+            .iter()
+            .find(|food| food.col == col && food.row == row)
+    }
+
+    pub fn special_food(&self) -> bool {
+        self.special_food
+    }
+
+    pub fn set_special_food(&mut self, special_food: bool) {
+        self.special_food = special_food;
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+
+        let ticks = self.ticks;
+        let expired: Vec<Food> = self
+            .food
+            .iter()
+            .filter(|food| {
+                food.expires_at
+                    .is_some_and(|expires_at| expires_at <= ticks)
+            })
+            .copied()
+            .collect();
+
+        for food in &expired {
+            self.set_cell(food.col, food.row, CellType::Empty);
+            self.emit(GameEvent::FoodExpired(food.kind));
+        }
+
+        if !expired.is_empty() {
+            self.replenish_food();
+        }
+    }
+
+    pub fn food_count(&self) -> usize {
+        self.food_count
+    }
+
+    pub fn set_food_count(&mut self, count: usize) {
+        self.food_count = count.clamp(1, self.max_food);
+    }
+
+    pub fn max_food(&self) -> usize {
+        self.max_food
+    }
+
+    pub fn set_max_food(&mut self, max: usize) {
+        self.max_food = max.max(1);
+        self.food_count = self.food_count.min(self.max_food);
     }
 
     pub fn reset(&mut self) {
-        for cell in &mut self.cells {
-            cell.cell = CellType::Empty;
+        self.food.clear();
+        self.ticks = 0;
+        for (cell, layout) in self.cells.iter_mut().zip(&self.layout) {
+            cell.cell = *layout;
+            if let CellType::Food(kind) = cell.cell {
+                self.food.push(Food {
+                    col: cell.col,
+                    row: cell.row,
+                    kind,
+                    spawned_at: 0,
+                    expires_at: kind.lifetime(),
+                });
+            }
         }
+        self.occupied.fill(0);
         self.game_over = false;
-        self.generate_food();
+        self.victory = false;
+        if self.seed.is_some() {
+            let seed = self.rng.next_u64();
+            self.reseed(seed);
+        }
     }
 
-    pub fn generate_food(&mut self) {
-        let mut rng = thread_rng();
-        let mut rand_row = rng.gen_range(0..self.rows);
-        let mut rand_col = rng.gen_range(0..self.columns);
+    pub fn replenish_food(&mut self) {
+        while self.food.len() < self.food_count && self.generate_food() {}
+    }
+
+    pub fn generate_food(&mut self) -> bool {
+        if self.food.len() >= self.max_food {
+            return false;
+        }
+
+        let food = if self.special_food {
+            CellType::Food(FoodKind::random(&mut self.rng))
+        } else {
+            CellType::Food(FoodKind::Normal)
+        };
+
+        for _ in 0..FOOD_ATTEMPTS {
+            let index = self.rng.gen_range(0..self.cells.len());
+            if self.cells[index].cell == CellType::Empty {
+                self.fill(index, food);
+                return true;
+            }
+        }
+
+        let empty: Vec<usize> = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.cell == CellType::Empty)
+            .map(|(index, _)| index)
+            .collect();
 
-        while !self.is_empty(rand_col, rand_row) {
-            rand_row = rng.gen_range(0..self.rows);
-            rand_col = rng.gen_range(0..self.columns);
+        if empty.is_empty() {
+            return false;
         }
 
-        self.set_cell(rand_col, rand_row, CellType::Food);
+        let index = empty[self.rng.gen_range(0..empty.len())];
+        self.fill(index, food);
+        true
     }
 
-    pub(crate) fn food_eaten_event(&self) {
-        if let Some(event_sender) = &self.event_sender {
-            let _ = event_sender.send(GameEvent::FoodEaten);
+    pub fn subscribe(&mut self, observer: impl GameObserver + Send + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    pub(crate) fn emit(&mut self, event: GameEvent) {
+        for observer in &mut self.observers {
+            observer.notify(&event);
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ai::Autopilot, Game, Snake, SnakeConfig};
+
+    fn food_history(seed: u64, steps: usize) -> Vec<Vec<(usize, usize, FoodKind)>> {
+        let mut board = Board::with_seed(20, 20, seed);
+        board.set_food_count(3);
+        let snake = Snake::new(SnakeConfig::default(), &board).unwrap();
+        let mut game = Game::new(board, snake);
+        let mut controllers = [Autopilot::new()];
+
+        (0..steps)
+            .map(|_| {
+                game.step_with(&mut controllers);
+                game.board()
+                    .food()
+                    .iter()
+                    .map(|food| (food.col(), food.row(), food.kind()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn boards_and_games_are_send() {
+        fn assert_send<T: Send>() {}
+
+        assert_send::<Board>();
+        assert_send::<Game>();
+    }
+
+    #[test]
+    fn same_seed_places_the_same_food() {
+        let history = food_history(7, 200);
+
+        assert_eq!(history, food_history(7, 200));
+        assert!(history.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn reseeding_replays_food_placement() {
+        let mut first = Board::with_seed(10, 10, 42);
+        let mut second = Board::with_seed(10, 10, 1);
+        second.reseed(42);
 
-    pub(crate) fn change_direction_event(&self) {
-        if let Some(event_sender) = &self.event_sender {
-            let _ = event_sender.send(GameEvent::SnakeChangedDirection);
+        for _ in 0..20 {
+            assert!(first.generate_food());
+            assert!(second.generate_food());
         }
+        assert_eq!(
+            first
+                .food()
+                .iter()
+                .map(|food| (food.col(), food.row()))
+                .collect::<Vec<_>>(),
+            second
+                .food()
+                .iter()
+                .map(|food| (food.col(), food.row()))
+                .collect::<Vec<_>>(),
+        );
     }
 }
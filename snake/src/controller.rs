@@ -0,0 +1,47 @@
+use super::{Board, Direction, Game, Score, Snake};
+
+/// The game as seen by one player's controller.
+pub struct GameView<'a> {
+    game: &'a Game,
+    player: usize,
+}
+
+impl<'a> GameView<'a> {
+    pub fn new(game: &'a Game, player: usize) -> Self {
+        Self { game, player }
+    }
+
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    pub fn game(&self) -> &'a Game {
+        self.game
+    }
+
+    pub fn board(&self) -> &'a Board {
+        self.game.board()
+    }
+
+    /// The snake this controller steers.
+    pub fn snake(&self) -> &'a Snake {
+        &self.game.snakes()[self.player]
+    }
+
+    pub fn score(&self) -> &'a Score {
+        &self.game.scores()[self.player]
+    }
+}
+
+/// Steers one snake. [`Game::step_with`] asks every controller whose snake is
+/// still on the board for a move once per step.
+pub trait Controller {
+    /// The direction to turn to, `None` to keep going.
+    fn decide(&mut self, view: &GameView) -> Option<Direction>;
+}
+
+impl<C: Controller + ?Sized> Controller for Box<C> {
+    fn decide(&mut self, view: &GameView) -> Option<Direction> {
+        (**self).decide(view)
+    }
+}
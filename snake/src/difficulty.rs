@@ -0,0 +1,81 @@
+use std::{str::FromStr, time::Duration};
+
+const SPEED_STEP: Duration = Duration::from_millis(5);
+
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Insane => "insane",
+        }
+    }
+
+    pub fn base_interval(self) -> Duration {
+        Duration::from_millis(match self {
+            Difficulty::Easy => 120,
+            Difficulty::Normal => 90,
+            Difficulty::Hard => 65,
+            Difficulty::Insane => 45,
+        })
+    }
+
+    pub fn min_interval(self) -> Duration {
+        Duration::from_millis(match self {
+            Difficulty::Easy => 80,
+            Difficulty::Normal => 50,
+            Difficulty::Hard => 35,
+            Difficulty::Insane => 25,
+        })
+    }
+
+    /// Points needed to reach the next speed level.
+    pub fn points_per_level(self) -> u64 {
+        match self {
+            Difficulty::Easy => 200,
+            Difficulty::Normal => 150,
+            Difficulty::Hard => 100,
+            Difficulty::Insane => 60,
+        }
+    }
+
+    pub fn max_speed_level(self) -> u32 {
+        let range = self.base_interval() - self.min_interval();
+        (range.as_millis() / SPEED_STEP.as_millis()) as u32 + 1
+    }
+
+    pub fn speed_level(self, points: u64) -> u32 {
+        let level = (points / self.points_per_level()).min(u32::MAX as u64) as u32 + 1;
+        level.min(self.max_speed_level())
+    }
+
+    pub fn tick_interval(self, points: u64) -> Duration {
+        self.base_interval() - SPEED_STEP * (self.speed_level(points) - 1)
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            "insane" => Ok(Difficulty::Insane),
+            _ => Err(format!(
+                "unknown difficulty `{s}`, expected easy, normal, hard or insane"
+            )),
+        }
+    }
+}
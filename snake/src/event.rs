@@ -0,0 +1,40 @@
+use std::sync::mpsc::{Sender, SyncSender};
+
+use super::FoodKind;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GameEvent {
+    FoodEaten(FoodKind),
+    FoodExpired(FoodKind),
+    SnakeDied,
+    SnakeChangedDirection,
+    BoardCleared,
+    Scored { points: i64, multiplier: u32 },
+}
+
+/// Receives every [`GameEvent`] emitted by a [`Board`](super::Board).
+///
+/// Observers are called synchronously from the tick, so they must not block.
+/// Channel senders forward events to another thread: a full `SyncSender`
+/// drops the event rather than waiting for the receiver to catch up.
+pub trait GameObserver {
+    fn notify(&mut self, event: &GameEvent);
+}
+
+impl<F: FnMut(&GameEvent)> GameObserver for F {
+    fn notify(&mut self, event: &GameEvent) {
+        self(event)
+    }
+}
+
+impl GameObserver for Sender<GameEvent> {
+    fn notify(&mut self, event: &GameEvent) {
+        let _ = self.send(*event);
+    }
+}
+
+impl GameObserver for SyncSender<GameEvent> {
+    fn notify(&mut self, event: &GameEvent) {
+        let _ = self.try_send(*event);
+    }
+}
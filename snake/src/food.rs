@@ -0,0 +1,89 @@
+use rand::Rng;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FoodKind {
+    Normal,
+    Bonus,
+    Golden,
+    Poison,
+    Shrink,
+}
+
+impl FoodKind {
+    const WEIGHTS: [(FoodKind, u32); 5] = [
+        (FoodKind::Normal, 75),
+        (FoodKind::Bonus, 10),
+        (FoodKind::Golden, 5),
+        (FoodKind::Poison, 6),
+        (FoodKind::Shrink, 4),
+    ];
+
+    pub(crate) fn random(rng: &mut impl Rng) -> Self {
+        let total: u32 = Self::WEIGHTS.iter().map(|(_, weight)| weight).sum();
+        let mut roll = rng.gen_range(0..total);
+
+        for (kind, weight) in Self::WEIGHTS {
+            if roll < weight {
+                return kind;
+            }
+            roll -= weight;
+        }
+
+        FoodKind::Normal
+    }
+
+    pub fn growth(self) -> usize {
+        match self {
+            FoodKind::Normal | FoodKind::Golden => 1,
+            FoodKind::Bonus => 3,
+            FoodKind::Poison | FoodKind::Shrink => 0,
+        }
+    }
+
+    pub fn shrink(self) -> usize {
+        match self {
+            FoodKind::Poison => 1,
+            FoodKind::Shrink => 3,
+            _ => 0,
+        }
+    }
+
+    /// Number of ticks before the food disappears, `None` if it never does.
+    pub fn lifetime(self) -> Option<u64> {
+        match self {
+            FoodKind::Golden => Some(50),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Food {
+    pub(crate) col: usize,
+    pub(crate) row: usize,
+    pub(crate) kind: FoodKind,
+    pub(crate) spawned_at: u64,
+    pub(crate) expires_at: Option<u64>,
+}
+
+impl Food {
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn kind(&self) -> FoodKind {
+        self.kind
+    }
+
+    pub fn spawned_at(&self) -> u64 {
+        self.spawned_at
+    }
+
+    pub fn expires_at(&self) -> Option<u64> {
+        self.expires_at
+    }
+}
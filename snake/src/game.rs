@@ -0,0 +1,556 @@
+use std::{str::FromStr, time::Duration};
+
+use super::{
+    Board, Controller, Difficulty, Direction, FoodKind, GameEvent, GameObserver, GameView, Score,
+    Snake, SnakeId,
+};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StepOutcome {
+    Moved,
+    FoodEaten(FoodKind),
+    Died,
+    Victory,
+    Paused,
+    GameOver,
+}
+
+/// What happens when snakes run head first into each other, either by moving
+/// into the same cell or by swapping places.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum HeadToHead {
+    #[default]
+    BothCrash,
+    /// The longest snake survives. Snakes of equal length all crash.
+    LongerWins,
+}
+
+impl FromStr for HeadToHead {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "both-crash" => Ok(HeadToHead::BothCrash),
+            "longer-wins" => Ok(HeadToHead::LongerWins),
+            _ => Err(format!(
+                "unknown head-to-head rule `{s}`, expected both-crash or longer-wins"
+            )),
+        }
+    }
+}
+
+pub struct Game {
+    board: Board,
+    snakes: Vec<Snake>,
+    scores: Vec<Score>,
+    crashed: Vec<bool>,
+    removed: Vec<bool>,
+    head_to_head: HeadToHead,
+    difficulty: Difficulty,
+}
+
+impl Game {
+    pub fn new(board: Board, snake: Snake) -> Self {
+        Self::with_snakes(board, vec![snake])
+    }
+
+    /// A game where every snake moves at once. Snakes that run into a wall or
+    /// another snake's body crash and are taken off the board, and meeting
+    /// heads are settled by [`HeadToHead`]. The round ends when at most one
+    /// snake is left, or when the only snake of a single-player game crashes.
+    pub fn with_snakes(board: Board, mut snakes: Vec<Snake>) -> Self {
+        assert!(!snakes.is_empty(), "a game needs at least one snake");
+
+        for (index, snake) in snakes.iter_mut().enumerate() {
+            snake.set_id(SnakeId::new(index));
+        }
+
+        let mut game = Self {
+            board,
+            scores: vec![Score::new(); snakes.len()],
+            crashed: vec![false; snakes.len()],
+            removed: vec![false; snakes.len()],
+            snakes,
+            head_to_head: HeadToHead::default(),
+            difficulty: Difficulty::default(),
+        };
+        for snake in &game.snakes {
+            snake.draw(&mut game.board);
+        }
+        game.board.replenish_food();
+        game
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn snake(&self) -> &Snake {
+        &self.snakes[0]
+    }
+
+    pub fn snakes(&self) -> &[Snake] {
+        &self.snakes
+    }
+
+    pub fn players(&self) -> usize {
+        self.snakes.len()
+    }
+
+    pub fn score(&self) -> &Score {
+        &self.scores[0]
+    }
+
+    pub fn scores(&self) -> &[Score] {
+        &self.scores
+    }
+
+    pub fn crashed(&self, player: usize) -> bool {
+        self.crashed[player]
+    }
+
+    /// Whether the snake is still drawn on the board. Snakes that crash in the
+    /// final move of a round stay on the board so players can see what happened.
+    pub fn on_board(&self, player: usize) -> bool {
+        !self.removed[player]
+    }
+
+    pub fn head_to_head(&self) -> HeadToHead {
+        self.head_to_head
+    }
+
+    pub fn set_head_to_head(&mut self, head_to_head: HeadToHead) {
+        self.head_to_head = head_to_head;
+    }
+
+    /// The only snake left standing once a multiplayer round is over, `None`
+    /// for a draw or a single-player game.
+    pub fn winner(&self) -> Option<usize> {
+        if self.snakes.len() < 2 || !self.board.game_over() {
+            return None;
+        }
+
+        let mut standing = (0..self.snakes.len()).filter(|&player| !self.crashed[player]);
+        match (standing.next(), standing.next()) {
+            (Some(player), None) => Some(player),
+            _ => None,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    pub fn speed_level(&self) -> u32 {
+        self.difficulty.speed_level(self.best_points())
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        self.difficulty.tick_interval(self.best_points())
+    }
+
+    fn best_points(&self) -> u64 {
+        self.scores.iter().map(Score::points).max().unwrap_or(0)
+    }
+
+    pub fn paused(&self) -> bool {
+        self.board.paused()
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.board.toggle_pause();
+    }
+
+    /// Sends the board's events to `observer` from now on.
+    pub fn subscribe(&mut self, observer: impl GameObserver + Send + 'static) {
+        self.board.subscribe(observer);
+    }
+
+    pub fn game_over(&self) -> bool {
+        self.board.game_over()
+    }
+
+    pub fn victory(&self) -> bool {
+        self.board.victory()
+    }
+
+    pub fn reset(&mut self) {
+        self.board.reset();
+        for snake in &mut self.snakes {
+            snake.reset();
+            snake.draw(&mut self.board);
+        }
+        self.scores.iter_mut().for_each(Score::reset);
+        self.crashed.fill(false);
+        self.removed.fill(false);
+        self.board.replenish_food();
+    }
+
+    pub fn turn(&mut self, direction: Direction) {
+        self.turn_player(0, direction);
+    }
+
+    pub fn turn_player(&mut self, player: usize, direction: Direction) {
+        if !self.board.game_over() && !self.board.paused() {
+            self.snakes[player].change_direction(direction, &mut self.board);
+        }
+    }
+
+    /// Steps the game with one controller per player, in player order.
+    pub fn step_with<C: Controller>(&mut self, controllers: &mut [C]) -> StepOutcome {
+        if !self.board.game_over() && !self.board.paused() {
+            for (player, controller) in controllers.iter_mut().enumerate() {
+                if !self.on_board(player) {
+                    continue;
+                }
+                if let Some(direction) = controller.decide(&GameView::new(self, player)) {
+                    self.turn_player(player, direction);
+                }
+            }
+        }
+
+        self.step(None)
+    }
+
+    pub fn step(&mut self, input: Option<Direction>) -> StepOutcome {
+        if self.board.game_over() {
+            return StepOutcome::GameOver;
+        }
+
+        if self.board.paused() {
+            return StepOutcome::Paused;
+        }
+
+        if let Some(direction) = input {
+            self.turn(direction);
+        }
+
+        let players = 0..self.snakes.len();
+        let targets: Vec<Option<(usize, usize)>> = self
+            .snakes
+            .iter_mut()
+            .zip(&self.crashed)
+            .map(|(snake, &crashed)| match crashed {
+                true => None,
+                false => snake.next_head(&self.board),
+            })
+            .collect();
+        let heads: Vec<(usize, usize)> = self.snakes.iter().map(Snake::head).collect();
+        let moving = |player: usize| !self.crashed[player];
+
+        let mut crashes = vec![false; self.snakes.len()];
+        for player in players.clone().filter(|&player| moving(player)) {
+            let Some((col, row)) = targets[player] else {
+                crashes[player] = true;
+                continue;
+            };
+
+            // Snakes swapping places meet head on instead of biting a body.
+            let swapping = players.clone().any(|other| {
+                other != player
+                    && moving(other)
+                    && heads[other] == (col, row)
+                    && targets[other] == Some(heads[player])
+            });
+            let meeting = players
+                .clone()
+                .any(|other| other != player && moving(other) && targets[other] == targets[player]);
+
+            if self.board.is_wall(col, row) || (self.board.is_occupied(col, row) && !swapping) {
+                crashes[player] = true;
+            } else if swapping || meeting {
+                crashes[player] = !self.wins_head_to_head(player, &targets, &heads);
+            }
+        }
+
+        let standing = players
+            .clone()
+            .filter(|&player| moving(player) && !crashes[player])
+            .count();
+        let round_over = standing == 0 || (self.snakes.len() > 1 && standing == 1);
+
+        for player in players.clone().filter(|&player| crashes[player]) {
+            self.crashed[player] = true;
+            self.board.emit(GameEvent::SnakeDied);
+            if !round_over {
+                self.removed[player] = true;
+                self.snakes[player].erase(&mut self.board);
+            }
+        }
+        if round_over {
+            self.board.finish();
+            return StepOutcome::Died;
+        }
+
+        for (player, target) in targets.into_iter().enumerate() {
+            if let (false, Some((col, row))) = (self.crashed[player], target) {
+                self.snakes[player].advance_to(col, row, &mut self.board);
+            }
+        }
+
+        let mut eaten_kind = None;
+        for player in players.filter(|&player| !self.crashed[player]) {
+            if let Some(food) = self.snakes[player].update(&mut self.board) {
+                let score = &mut self.scores[player];
+                let points = score.record(&food, self.board.ticks());
+                let multiplier = score.multiplier();
+                self.board.emit(GameEvent::Scored { points, multiplier });
+                eaten_kind = eaten_kind.or(Some(food.kind()));
+            }
+        }
+
+        if self.board.victory() {
+            return StepOutcome::Victory;
+        }
+
+        self.board.tick();
+        for score in &mut self.scores {
+            score.tick(self.board.ticks());
+        }
+        match eaten_kind {
+            Some(kind) => StepOutcome::FoodEaten(kind),
+            None => StepOutcome::Moved,
+        }
+    }
+
+    fn wins_head_to_head(
+        &self,
+        player: usize,
+        targets: &[Option<(usize, usize)>],
+        heads: &[(usize, usize)],
+    ) -> bool {
+        if self.head_to_head == HeadToHead::BothCrash {
+            return false;
+        }
+
+        let length = self.snakes[player].len();
+        (0..self.snakes.len())
+            .filter(|&other| other != player && !self.crashed[other])
+            .filter(|&other| {
+                targets[other] == targets[player]
+                    || (targets[other] == Some(heads[player])
+                        && targets[player] == Some(heads[other]))
+            })
+            .all(|other| self.snakes[other].len() < length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+    use crate::{snake::MAX_QUEUED_TURNS, Edges, SnakeConfig};
+
+    /// Builds a game on an empty 10x10 board from `(col, row, length, direction)`
+    /// for each snake.
+    fn game(snakes: &[(usize, usize, usize, Direction)], head_to_head: HeadToHead) -> Game {
+        let mut board = Board::with_seed(10, 10, 1);
+        board.set_special_food(false);
+        let snakes = snakes
+            .iter()
+            .map(|&(col, row, length, direction)| {
+                let config = SnakeConfig::new()
+                    .position(col, row)
+                    .length(length)
+                    .direction(direction);
+                Snake::new(config, &board).unwrap()
+            })
+            .collect();
+
+        let mut game = Game::with_snakes(board, snakes);
+        game.set_head_to_head(head_to_head);
+        game
+    }
+
+    fn meeting(first: usize, second: usize, head_to_head: HeadToHead) -> Game {
+        game(
+            &[
+                (3, 5, first, Direction::Right),
+                (5, 5, second, Direction::Left),
+            ],
+            head_to_head,
+        )
+    }
+
+    fn swapping(first: usize, second: usize, head_to_head: HeadToHead) -> Game {
+        game(
+            &[
+                (3, 5, first, Direction::Right),
+                (4, 5, second, Direction::Left),
+            ],
+            head_to_head,
+        )
+    }
+
+    fn single(direction: Direction) -> Game {
+        game(&[(3, 5, 3, direction)], HeadToHead::default())
+    }
+
+    /// The direction the snake heads in after each of `steps` steps.
+    fn directions(game: &mut Game, steps: usize) -> Vec<Direction> {
+        (0..steps)
+            .map(|_| {
+                game.step(None);
+                *game.snake().direction()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn queued_turns_apply_one_per_step() {
+        let mut game = single(Direction::Right);
+        game.turn(Direction::Up);
+        game.turn(Direction::Left);
+
+        assert_eq!(
+            directions(&mut game, 3),
+            [Direction::Up, Direction::Left, Direction::Left]
+        );
+        assert_eq!(game.snake().head(), (1, 4));
+    }
+
+    #[test]
+    fn reversing_into_the_neck_is_dropped() {
+        let mut game = single(Direction::Right);
+        game.turn(Direction::Left);
+        assert_eq!(directions(&mut game, 1), [Direction::Right]);
+
+        // A reversal is judged against the last queued turn.
+        game.turn(Direction::Up);
+        game.turn(Direction::Down);
+        assert_eq!(directions(&mut game, 2), [Direction::Up, Direction::Up]);
+    }
+
+    #[test]
+    fn queue_keeps_at_most_max_queued_turns() {
+        let turns = [
+            Direction::Up,
+            Direction::Left,
+            Direction::Down,
+            Direction::Right,
+            Direction::Up,
+            Direction::Left,
+        ];
+        let mut game = single(Direction::Right);
+        for &direction in &turns[..=MAX_QUEUED_TURNS] {
+            game.turn(direction);
+        }
+
+        let mut expected = turns[..MAX_QUEUED_TURNS].to_vec();
+        expected.push(turns[MAX_QUEUED_TURNS - 1]);
+        assert_eq!(directions(&mut game, MAX_QUEUED_TURNS + 1), expected);
+    }
+
+    #[test]
+    fn meeting_heads_both_crash() {
+        let mut game = meeting(4, 3, HeadToHead::BothCrash);
+
+        assert_eq!(game.step(None), StepOutcome::Died);
+        assert!(game.game_over());
+        assert!(game.crashed(0) && game.crashed(1));
+        assert_eq!(game.winner(), None);
+    }
+
+    #[test]
+    fn swapping_heads_both_crash() {
+        let mut game = swapping(4, 3, HeadToHead::BothCrash);
+
+        assert_eq!(game.step(None), StepOutcome::Died);
+        assert!(game.crashed(0) && game.crashed(1));
+        assert_eq!(game.winner(), None);
+    }
+
+    #[test]
+    fn longer_snake_wins_meeting_heads() {
+        let mut game = meeting(3, 4, HeadToHead::LongerWins);
+
+        assert_eq!(game.step(None), StepOutcome::Died);
+        assert!(game.crashed(0) && !game.crashed(1));
+        assert_eq!(game.winner(), Some(1));
+    }
+
+    #[test]
+    fn longer_snake_wins_swapping_heads() {
+        let mut game = swapping(4, 3, HeadToHead::LongerWins);
+
+        assert_eq!(game.step(None), StepOutcome::Died);
+        assert!(!game.crashed(0) && game.crashed(1));
+        assert_eq!(game.winner(), Some(0));
+    }
+
+    #[test]
+    fn equal_lengths_both_crash_when_longer_wins() {
+        for mut game in [
+            meeting(3, 3, HeadToHead::LongerWins),
+            swapping(3, 3, HeadToHead::LongerWins),
+        ] {
+            assert_eq!(game.step(None), StepOutcome::Died);
+            assert!(game.crashed(0) && game.crashed(1));
+            assert_eq!(game.winner(), None);
+        }
+    }
+
+    #[test]
+    fn crashed_snake_is_removed_while_the_round_continues() {
+        // The third snake runs into the second one's body.
+        let mut game = game(
+            &[
+                (3, 1, 3, Direction::Right),
+                (3, 5, 3, Direction::Right),
+                (2, 6, 2, Direction::Up),
+            ],
+            HeadToHead::BothCrash,
+        );
+        let (sender, events) = channel();
+        game.subscribe(sender);
+
+        assert_ne!(game.step(None), StepOutcome::Died);
+        assert!(!game.game_over());
+        assert!(game.crashed(2) && !game.on_board(2));
+        assert!(!game.crashed(0) && !game.crashed(1));
+        assert_eq!(game.board().snake_at(2, 6), None);
+        assert_eq!(game.board().snake_at(2, 7), None);
+        assert_eq!(
+            events
+                .try_iter()
+                .filter(|event| *event == GameEvent::SnakeDied)
+                .count(),
+            1
+        );
+
+        // The remaining snakes keep moving until one of them is left.
+        for _ in 0..3 {
+            game.step(None);
+        }
+        assert!(!game.game_over());
+        assert_eq!(game.snakes()[0].head(), (7, 1));
+        assert_eq!(game.snakes()[2].head(), (2, 6));
+    }
+
+    #[test]
+    fn round_ends_with_the_last_snake_standing() {
+        // The third snake bites the second, which then runs into the wall.
+        let mut game = game(
+            &[
+                (3, 1, 3, Direction::Right),
+                (8, 5, 3, Direction::Right),
+                (7, 6, 2, Direction::Up),
+            ],
+            HeadToHead::BothCrash,
+        );
+        game.board.set_edges(Edges::walls());
+
+        assert_ne!(game.step(None), StepOutcome::Died);
+        assert!(!game.on_board(2));
+
+        assert_eq!(game.step(None), StepOutcome::Died);
+        assert!(game.game_over());
+        assert_eq!(game.winner(), Some(0));
+        assert!(game.crashed(1) && game.on_board(1));
+    }
+}
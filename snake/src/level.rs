@@ -0,0 +1,416 @@
+use std::{error::Error, fmt, fs, io, path::Path, str::FromStr};
+
+use super::{Board, CellType, Direction, Edges, FoodKind, Snake, SnakeConfig, SpawnError};
+
+#[derive(Debug)]
+pub enum LevelErrorKind {
+    Io(io::Error),
+    UnknownKey(String),
+    InvalidValue { key: String, value: String },
+    UnexpectedChar { col: usize, found: char },
+    RaggedRow { expected: usize, found: usize },
+    MissingGrid,
+    MissingHead,
+    MultipleHeads,
+    Spawn(SpawnError),
+}
+
+#[derive(Debug)]
+pub struct LevelError {
+    line: usize,
+    kind: LevelErrorKind,
+}
+
+impl LevelError {
+    fn new(line: usize, kind: LevelErrorKind) -> Self {
+        Self { line, kind }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn kind(&self) -> &LevelErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: ", self.line)?;
+        }
+
+        match &self.kind {
+            LevelErrorKind::Io(error) => write!(f, "{error}"),
+            LevelErrorKind::UnknownKey(key) => write!(f, "unknown key `{key}`"),
+            LevelErrorKind::InvalidValue { key, value } => {
+                write!(f, "invalid value `{value}` for `{key}`")
+            }
+            LevelErrorKind::UnexpectedChar { col, found } => {
+                write!(f, "unexpected character `{found}` in column {}", col + 1)
+            }
+            LevelErrorKind::RaggedRow { expected, found } => {
+                write!(f, "row has {found} cells but the first row has {expected}")
+            }
+            LevelErrorKind::MissingGrid => write!(f, "level has no grid"),
+            LevelErrorKind::MissingHead => {
+                write!(
+                    f,
+                    "level has no snake head, place one of `>`, `<`, `^` or `v`"
+                )
+            }
+            LevelErrorKind::MultipleHeads => write!(f, "level has more than one snake head"),
+            LevelErrorKind::Spawn(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for LevelError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            LevelErrorKind::Io(error) => Some(error),
+            LevelErrorKind::Spawn(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A board layout read from a plain-text file.
+///
+/// Optional `key = value` settings (`edges`, `length`, `food`) come first, followed
+/// by the grid: `.` is empty, `#` is a wall, `*` is food and one of `>`,
+/// `<`, `^` or `v` is the snake head facing that way. The body extends
+/// behind the head. Lines starting with `;` are comments.
+#[derive(Clone, Debug)]
+pub struct Level {
+    rows: usize,
+    columns: usize,
+    edges: Edges,
+    food_count: usize,
+    walls: Vec<(usize, usize)>,
+    food: Vec<(usize, usize)>,
+    head: (usize, usize),
+    direction: Direction,
+    length: usize,
+    head_line: usize,
+}
+
+impl Level {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LevelError> {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| LevelError::new(0, LevelErrorKind::Io(error)))?;
+        contents.parse()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn edges(&self) -> Edges {
+        self.edges
+    }
+
+    /// Overrides the level's edges. Set them before [`Level::build`] so the
+    /// snake is checked against the edges it will play on.
+    pub fn set_edges(&mut self, edges: Edges) {
+        self.edges = edges;
+    }
+
+    pub fn food_count(&self) -> usize {
+        self.food_count
+    }
+
+    pub fn build(&self, seed: Option<u64>) -> Result<(Board, Snake), LevelError> {
+        let mut board = match seed {
+            Some(seed) => Board::with_seed(self.rows, self.columns, seed),
+            None => Board::new(self.rows, self.columns),
+        };
+        board.set_edges(self.edges);
+        board.set_food_count(self.food_count);
+
+        for &(col, row) in &self.walls {
+            board.set_layout_cell(col, row, CellType::Wall);
+        }
+        for &(col, row) in &self.food {
+            board.set_layout_cell(col, row, CellType::Food(FoodKind::Normal));
+        }
+
+        let config = SnakeConfig::new()
+            .position(self.head.0, self.head.1)
+            .length(self.length)
+            .direction(self.direction);
+        let snake = Snake::new(config, &board)
+            .map_err(|error| LevelError::new(self.head_line, LevelErrorKind::Spawn(error)))?;
+
+        Ok((board, snake))
+    }
+}
+
+impl FromStr for Level {
+    type Err = LevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut edges = Edges::default();
+        let mut length = 4;
+        let mut food_count = 1;
+        let mut grid: Vec<(usize, &str)> = Vec::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                let (key, value) = (key.trim(), value.trim());
+                let invalid = || {
+                    LevelError::new(
+                        number,
+                        LevelErrorKind::InvalidValue {
+                            key: key.to_owned(),
+                            value: value.to_owned(),
+                        },
+                    )
+                };
+
+                match key {
+                    "edges" => edges = value.parse().map_err(|_| invalid())?,
+                    "length" => match value.parse() {
+                        Ok(value) if value > 0 => length = value,
+                        _ => return Err(invalid()),
+                    },
+                    "food" => match value.parse() {
+                        Ok(value) if value > 0 => food_count = value,
+                        _ => return Err(invalid()),
+                    },
+                    _ => {
+                        return Err(LevelError::new(
+                            number,
+                            LevelErrorKind::UnknownKey(key.to_owned()),
+                        ))
+                    }
+                }
+            } else {
+                grid.push((number, line));
+            }
+        }
+
+        let Some(&(_, first)) = grid.first() else {
+            return Err(LevelError::new(0, LevelErrorKind::MissingGrid));
+        };
+        let columns = first.chars().count();
+
+        let mut walls = Vec::new();
+        let mut food = Vec::new();
+        let mut head = None;
+
+        for (row, &(number, line)) in grid.iter().enumerate() {
+            let found = line.chars().count();
+            if found != columns {
+                return Err(LevelError::new(
+                    number,
+                    LevelErrorKind::RaggedRow {
+                        expected: columns,
+                        found,
+                    },
+                ));
+            }
+
+            for (col, c) in line.chars().enumerate() {
+                let direction = match c {
+                    '.' => continue,
+                    '#' => {
+                        walls.push((col, row));
+                        continue;
+                    }
+                    '*' => {
+                        food.push((col, row));
+                        continue;
+                    }
+                    '^' => Direction::Up,
+                    'v' => Direction::Down,
+                    '<' => Direction::Left,
+                    '>' => Direction::Right,
+                    found => {
+                        return Err(LevelError::new(
+                            number,
+                            LevelErrorKind::UnexpectedChar { col, found },
+                        ))
+                    }
+                };
+
+                if head.is_some() {
+                    return Err(LevelError::new(number, LevelErrorKind::MultipleHeads));
+                }
+                head = Some(((col, row), direction, number));
+            }
+        }
+
+        let Some((head, direction, head_line)) = head else {
+            return Err(LevelError::new(0, LevelErrorKind::MissingHead));
+        };
+
+        Ok(Self {
+            rows: grid.len(),
+            columns,
+            edges,
+            food_count,
+            walls,
+            food,
+            head,
+            direction,
+            length,
+            head_line,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(level: &str) -> LevelError {
+        match level.parse::<Level>() {
+            Ok(_) => panic!("level should not parse:\n{level}"),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    fn parses_settings_and_grid() {
+        let level: Level = "; arena\nedges = walls\nfood = 2\n#####\n#>..#\n#.*.#\n#####"
+            .parse()
+            .unwrap();
+
+        assert_eq!((level.rows(), level.columns()), (4, 5));
+        assert_eq!(level.edges(), Edges::walls());
+        assert_eq!(level.food_count(), 2);
+    }
+
+    #[test]
+    fn missing_grid() {
+        let error = error("edges = wrap\n; nothing else");
+
+        assert!(matches!(error.kind(), LevelErrorKind::MissingGrid));
+        assert_eq!(error.line(), 0);
+        assert_eq!(error.to_string(), "level has no grid");
+    }
+
+    #[test]
+    fn ragged_row() {
+        let error = error(".....\n.>...\n...");
+
+        assert!(matches!(
+            error.kind(),
+            LevelErrorKind::RaggedRow {
+                expected: 5,
+                found: 3
+            }
+        ));
+        assert_eq!(error.line(), 3);
+        assert_eq!(
+            error.to_string(),
+            "line 3: row has 3 cells but the first row has 5"
+        );
+    }
+
+    #[test]
+    fn multiple_heads() {
+        let error = error("; two snakes\n.>...\n...<.");
+
+        assert!(matches!(error.kind(), LevelErrorKind::MultipleHeads));
+        assert_eq!(error.line(), 3);
+        assert_eq!(
+            error.to_string(),
+            "line 3: level has more than one snake head"
+        );
+    }
+
+    #[test]
+    fn missing_head() {
+        let error = error("....\n....");
+
+        assert!(matches!(error.kind(), LevelErrorKind::MissingHead));
+        assert_eq!(error.line(), 0);
+    }
+
+    #[test]
+    fn unexpected_char() {
+        let error = error(".>..\n..x.");
+
+        assert!(matches!(
+            error.kind(),
+            LevelErrorKind::UnexpectedChar { col: 2, found: 'x' }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "line 2: unexpected character `x` in column 3"
+        );
+    }
+
+    #[test]
+    fn unknown_key() {
+        let error = error("edges = wrap\nspeed = 3\n.>..");
+
+        assert!(matches!(error.kind(), LevelErrorKind::UnknownKey(key) if key == "speed"));
+        assert_eq!(error.line(), 2);
+        assert_eq!(error.to_string(), "line 2: unknown key `speed`");
+    }
+
+    #[test]
+    fn invalid_value() {
+        for (level, key, value) in [
+            ("edges = sideways\n.>..", "edges", "sideways"),
+            ("length = 0\n.>..", "length", "0"),
+            ("food = many\n.>..", "food", "many"),
+        ] {
+            let error = error(level);
+
+            assert_eq!(error.line(), 1);
+            assert_eq!(
+                error.to_string(),
+                format!("line 1: invalid value `{value}` for `{key}`")
+            );
+        }
+    }
+
+    #[test]
+    fn snake_that_does_not_fit() {
+        let level: Level = "; short arena\nlength = 4\n#..\n#>.\n#..".parse().unwrap();
+
+        let Err(error) = level.build(Some(1)) else {
+            panic!("snake should not fit");
+        };
+        assert!(matches!(
+            error.kind(),
+            LevelErrorKind::Spawn(SpawnError::Blocked { col: 0, row: 1 })
+        ));
+        assert_eq!(error.line(), 4);
+        assert_eq!(
+            error.to_string(),
+            "line 4: snake body does not fit, (0, 1) is blocked"
+        );
+    }
+
+    #[test]
+    fn overridden_edges_apply_to_the_spawn() {
+        let mut level: Level = "length = 3\n....\n>...".parse().unwrap();
+        assert!(level.build(Some(1)).is_ok());
+
+        level.set_edges(Edges::walls());
+        let Err(error) = level.build(Some(1)) else {
+            panic!("snake should not wrap through a wall");
+        };
+        assert!(matches!(
+            error.kind(),
+            LevelErrorKind::Spawn(SpawnError::Blocked { col: 0, row: 1 })
+        ));
+    }
+}
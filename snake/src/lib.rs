@@ -1,5 +1,20 @@
+pub mod ai;
 mod board;
+mod controller;
+mod difficulty;
+mod event;
+mod food;
+mod game;
+mod level;
+mod score;
 mod snake;
 
 pub use crate::snake::*;
 pub use board::*;
+pub use controller::*;
+pub use difficulty::*;
+pub use event::*;
+pub use food::*;
+pub use game::*;
+pub use level::*;
+pub use score::*;
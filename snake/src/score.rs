@@ -0,0 +1,169 @@
+use super::{Food, FoodKind};
+
+const COMBO_WINDOW: u64 = 20;
+const MAX_MULTIPLIER: u32 = 5;
+const TIME_BONUS_WINDOW: u64 = 30;
+
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct ScoreBreakdown {
+    pub food: u64,
+    pub combo: u64,
+    pub time: u64,
+    pub penalty: u64,
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Score {
+    points: u64,
+    multiplier: u32,
+    best_multiplier: u32,
+    eaten: u32,
+    last_eaten_at: Option<u64>,
+    breakdown: ScoreBreakdown,
+}
+
+impl Score {
+    pub fn new() -> Self {
+        Self {
+            multiplier: 1,
+            best_multiplier: 1,
+            ..Default::default()
+        }
+    }
+
+    pub fn points(&self) -> u64 {
+        self.points
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        self.multiplier
+    }
+
+    pub fn best_multiplier(&self) -> u32 {
+        self.best_multiplier
+    }
+
+    pub fn eaten(&self) -> u32 {
+        self.eaten
+    }
+
+    pub fn breakdown(&self) -> &ScoreBreakdown {
+        &self.breakdown
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    pub(crate) fn tick(&mut self, ticks: u64) {
+        if self
+            .last_eaten_at
+            .is_some_and(|last| ticks.saturating_sub(last) > COMBO_WINDOW)
+        {
+            self.multiplier = 1;
+        }
+    }
+
+    /// Records an eaten food and returns the points it was worth.
+    pub(crate) fn record(&mut self, food: &Food, ticks: u64) -> i64 {
+        self.eaten += 1;
+
+        if food.kind == FoodKind::Poison {
+            let penalty = base_points(food.kind).min(self.points);
+            self.points -= penalty;
+            self.breakdown.penalty += penalty;
+            self.multiplier = 1;
+            self.last_eaten_at = None;
+            return -(penalty as i64);
+        }
+
+        if self
+            .last_eaten_at
+            .is_some_and(|last| ticks.saturating_sub(last) <= COMBO_WINDOW)
+        {
+            self.multiplier = (self.multiplier + 1).min(MAX_MULTIPLIER);
+            self.best_multiplier = self.best_multiplier.max(self.multiplier);
+        }
+        self.last_eaten_at = Some(ticks);
+
+        let base = base_points(food.kind);
+        let combo = base * (self.multiplier as u64 - 1);
+        let time = TIME_BONUS_WINDOW.saturating_sub(ticks.saturating_sub(food.spawned_at)) / 3;
+
+        self.breakdown.food += base;
+        self.breakdown.combo += combo;
+        self.breakdown.time += time;
+
+        let points = base + combo + time;
+        self.points += points;
+        points as i64
+    }
+}
+
+fn base_points(kind: FoodKind) -> u64 {
+    match kind {
+        FoodKind::Normal => 10,
+        FoodKind::Bonus => 30,
+        FoodKind::Golden => 50,
+        FoodKind::Poison => 20,
+        FoodKind::Shrink => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Spawned long enough ago that eating it earns no time bonus.
+    fn food(kind: FoodKind) -> Food {
+        Food {
+            col: 0,
+            row: 0,
+            kind,
+            spawned_at: 0,
+            expires_at: None,
+        }
+    }
+
+    #[test]
+    fn poison_never_takes_the_score_below_zero() {
+        let mut score = Score::new();
+
+        assert_eq!(score.record(&food(FoodKind::Poison), 100), 0);
+        assert_eq!(score.points(), 0);
+
+        score.record(&food(FoodKind::Normal), 100);
+        assert_eq!(score.record(&food(FoodKind::Poison), 200), -10);
+        assert_eq!(score.points(), 0);
+        assert_eq!(score.breakdown().penalty, 10);
+    }
+
+    #[test]
+    fn multiplier_grows_within_the_combo_window_up_to_the_cap() {
+        let mut score = Score::new();
+
+        let multipliers: Vec<u32> = (0..7)
+            .map(|eaten| {
+                score.record(&food(FoodKind::Normal), 100 + eaten * COMBO_WINDOW);
+                score.multiplier()
+            })
+            .collect();
+
+        assert_eq!(multipliers, [1, 2, 3, 4, 5, 5, 5]);
+        assert_eq!(score.best_multiplier(), MAX_MULTIPLIER);
+    }
+
+    #[test]
+    fn multiplier_drops_back_once_the_window_passes() {
+        let mut score = Score::new();
+        score.record(&food(FoodKind::Normal), 100);
+        score.record(&food(FoodKind::Normal), 110);
+
+        score.tick(110 + COMBO_WINDOW);
+        assert_eq!(score.multiplier(), 2);
+
+        score.tick(111 + COMBO_WINDOW);
+        assert_eq!(score.multiplier(), 1);
+        assert_eq!(score.best_multiplier(), 2);
+    }
+}
@@ -1,12 +1,15 @@
-use super::{Board, CellType};
-use std::collections::LinkedList;
+use super::{Board, CellType, Food, GameEvent};
+use std::{collections::VecDeque, error::Error, fmt};
 
+pub(crate) const MAX_QUEUED_TURNS: usize = 3;
+
+#[derive(Clone)]
 struct Part {
     col: usize,
     row: usize,
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Direction {
     Up,
     Left,
@@ -14,180 +17,360 @@ pub enum Direction {
     Right,
 }
 
-pub struct Snake {
+impl Direction {
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    fn between(from: &Part, to: &Part) -> Option<Self> {
+        let horizontal = to.col as isize - from.col as isize;
+        let vertical = to.row as isize - from.row as isize;
+
+        // A step longer than one cell means the snake wrapped around the board.
+        match (horizontal.signum(), vertical.signum()) {
+            (1, 0) if horizontal == 1 => Some(Direction::Right),
+            (1, 0) => Some(Direction::Left),
+            (-1, 0) if horizontal == -1 => Some(Direction::Left),
+            (-1, 0) => Some(Direction::Right),
+            (0, 1) if vertical == 1 => Some(Direction::Down),
+            (0, 1) => Some(Direction::Up),
+            (0, -1) if vertical == -1 => Some(Direction::Up),
+            (0, -1) => Some(Direction::Down),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Segment {
+    col: usize,
+    row: usize,
+    incoming: Option<Direction>,
+    outgoing: Option<Direction>,
+}
+
+impl Segment {
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The direction the body travelled to enter this segment, `None` for the tail.
+    pub fn incoming(&self) -> Option<Direction> {
+        self.incoming
+    }
+
+    /// The direction the body leaves this segment towards the head. For the
+    /// head itself this is the direction the snake is moving.
+    pub fn outgoing(&self) -> Option<Direction> {
+        self.outgoing
+    }
+}
+
+/// Which snake owns a cell. Games number their snakes from zero in the order
+/// they were added.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct SnakeId(usize);
+
+impl SnakeId {
+    pub fn new(index: usize) -> Self {
+        Self(index)
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SpawnError {
+    ZeroLength,
+    OutOfBounds { col: usize, row: usize },
+    Blocked { col: usize, row: usize },
+}
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnError::ZeroLength => write!(f, "snake must be at least one part long"),
+            SpawnError::OutOfBounds { col, row } => {
+                write!(f, "snake head ({col}, {row}) is outside the board")
+            }
+            SpawnError::Blocked { col, row } => {
+                write!(f, "snake body does not fit, ({col}, {row}) is blocked")
+            }
+        }
+    }
+}
+
+impl Error for SpawnError {}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SnakeConfig {
+    col: usize,
+    row: usize,
+    length: usize,
     direction: Direction,
-    parts: LinkedList<Part>,
 }
 
-impl Default for Snake {
+impl Default for SnakeConfig {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl Snake {
+impl SnakeConfig {
     pub fn new() -> Self {
-        let mut parts = LinkedList::new();
-        parts.push_back(Part { col: 5, row: 2 });
-        parts.push_back(Part { col: 4, row: 2 });
-        parts.push_back(Part { col: 3, row: 2 });
-        parts.push_back(Part { col: 2, row: 2 });
-
         Self {
-            parts,
+            col: 5,
+            row: 2,
+            length: 4,
             direction: Direction::Right,
         }
     }
 
-    pub fn reset(&mut self) {
-        self.parts.clear();
-        self.parts.push_back(Part { col: 5, row: 2 });
-        self.parts.push_back(Part { col: 4, row: 2 });
-        self.parts.push_back(Part { col: 3, row: 2 });
-        self.parts.push_back(Part { col: 2, row: 2 });
-        self.direction = Direction::Right;
+    pub fn position(mut self, col: usize, row: usize) -> Self {
+        self.col = col;
+        self.row = row;
+        self
     }
 
-    pub fn pop_end(&mut self, board: &mut Board) {
-        if let Some(cell) = self.parts.pop_back() {
-            board.set_cell(cell.col, cell.row, CellType::Empty);
-        }
+    pub fn length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
     }
 
-    pub fn move_up(&mut self, board: &mut Board) {
-        let head = self.parts.front().unwrap();
-        let new_head = Part {
-            col: head.col,
-            row: if head.row == 0 {
-                board.rows() - 1
-            } else {
-                head.row - 1
-            },
-        };
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
 
-        if board.is_snake(new_head.col, new_head.row) {
-            board.end_game();
-            return;
+    pub fn validate(&self, board: &Board) -> Result<(), SpawnError> {
+        self.body(board).map(|_| ())
+    }
+
+    fn body(&self, board: &Board) -> Result<Vec<Part>, SpawnError> {
+        if self.length == 0 {
+            return Err(SpawnError::ZeroLength);
         }
 
-        self.parts.push_front(new_head);
-        self.pop_end(board);
-    }
+        let (col, row) = (self.col, self.row);
+        if col >= board.columns() || row >= board.rows() {
+            return Err(SpawnError::OutOfBounds { col, row });
+        }
+        if !board.is_empty(col, row) {
+            return Err(SpawnError::Blocked { col, row });
+        }
 
-    pub fn move_down(&mut self, board: &mut Board) {
-        let head = self.parts.front().unwrap();
-        let new_head = Part {
-            col: head.col,
-            row: (head.row + 1) % board.rows(),
-        };
+        let mut body = vec![Part { col, row }];
+        while body.len() < self.length {
+            let tail = &body[body.len() - 1];
+            let Some((col, row)) = board.neighbor(tail.col, tail.row, self.direction.opposite())
+            else {
+                return Err(SpawnError::Blocked {
+                    col: tail.col,
+                    row: tail.row,
+                });
+            };
 
-        if board.is_snake(new_head.col, new_head.row) {
-            board.end_game();
-            return;
+            if !board.is_empty(col, row) || body.iter().any(|p| p.col == col && p.row == row) {
+                return Err(SpawnError::Blocked { col, row });
+            }
+            body.push(Part { col, row });
         }
 
-        self.parts.push_front(new_head);
-        self.pop_end(board);
+        Ok(body)
     }
+}
 
-    pub fn move_left(&mut self, board: &mut Board) {
-        let head = self.parts.front().unwrap();
-        let new_head = Part {
-            col: if head.col == 0 {
-                board.columns() - 1
-            } else {
-                head.col - 1
-            },
-            row: head.row,
-        };
+pub struct Snake {
+    id: SnakeId,
+    direction: Direction,
+    turns: VecDeque<Direction>,
+    parts: VecDeque<Part>,
+    config: SnakeConfig,
+    spawn: Vec<Part>,
+}
 
-        if board.is_snake(new_head.col, new_head.row) {
-            board.end_game();
-            return;
-        }
+impl Snake {
+    pub fn new(config: SnakeConfig, board: &Board) -> Result<Self, SpawnError> {
+        let spawn = config.body(board)?;
 
-        self.parts.push_front(new_head);
-        self.pop_end(board);
+        Ok(Self {
+            id: SnakeId::default(),
+            parts: spawn.iter().cloned().collect(),
+            direction: config.direction,
+            turns: VecDeque::with_capacity(MAX_QUEUED_TURNS),
+            config,
+            spawn,
+        })
     }
 
-    pub fn move_right(&mut self, board: &mut Board) {
-        let head = self.parts.front().unwrap();
-        let new_head = Part {
-            col: (head.col + 1) % board.columns(),
-            row: head.row,
-        };
+    pub fn id(&self) -> SnakeId {
+        self.id
+    }
 
-        if board.is_snake(new_head.col, new_head.row) {
-            board.end_game();
-            return;
-        }
+    pub(crate) fn set_id(&mut self, id: SnakeId) {
+        self.id = id;
+    }
 
-        self.parts.push_front(new_head);
-        self.pop_end(board);
+    pub fn config(&self) -> &SnakeConfig {
+        &self.config
+    }
+
+    pub fn reset(&mut self) {
+        self.parts = self.spawn.iter().cloned().collect();
+        self.direction = self.config.direction;
+        self.turns.clear();
+    }
+
+    pub(crate) fn pop_end(&mut self, board: &mut Board) {
+        if let Some(cell) = self.parts.pop_back() {
+            let grown = self
+                .parts
+                .back()
+                .is_some_and(|tail| tail.col == cell.col && tail.row == cell.row);
+
+            if !grown {
+                board.set_cell(cell.col, cell.row, CellType::Empty);
+            }
+        }
     }
 
-    pub fn grow(&mut self, board: &mut Board) {
+    pub(crate) fn grow(&mut self, board: &mut Board) {
         let tail = self.parts.back().unwrap();
         let new_tail = Part {
             col: tail.col,
             row: tail.row,
         };
 
-        board.set_cell(new_tail.col, new_tail.row, CellType::Snake);
+        board.set_cell(new_tail.col, new_tail.row, CellType::Snake(self.id));
         self.parts.push_back(new_tail);
     }
 
+    pub(crate) fn shrink(&mut self, board: &mut Board) {
+        if self.parts.len() > 1 {
+            self.pop_end(board);
+        }
+    }
+
     pub fn change_direction(&mut self, dir: Direction, board: &mut Board) {
-        board.change_direction_event();
-        match self.direction {
-            Direction::Up if dir != Direction::Down => {
-                self.direction = dir;
-            }
-            Direction::Down if dir != Direction::Up => {
-                self.direction = dir;
-            }
-            Direction::Left if dir != Direction::Right => {
-                self.direction = dir;
-            }
-            Direction::Right if dir != Direction::Left => {
-                self.direction = dir;
-            }
-            _ => {}
-        };
+        let last = self.turns.back().unwrap_or(&self.direction);
+
+        if dir == *last || dir == last.opposite() || self.turns.len() == MAX_QUEUED_TURNS {
+            return;
+        }
+
+        board.emit(GameEvent::SnakeChangedDirection);
+        self.turns.push_back(dir);
     }
 
     pub fn direction(&self) -> &Direction {
         &self.direction
     }
 
-    pub fn update(&mut self, board: &mut Board) {
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    pub fn head(&self) -> (usize, usize) {
         let head = self.parts.front().unwrap();
+        (head.col, head.row)
+    }
 
-        if board.is_food(head.col, head.row) {
-            self.grow(board);
-            board.food_eaten_event();
-            board.generate_food();
-        }
+    pub fn tail(&self) -> (usize, usize) {
+        let tail = self.parts.back().unwrap();
+        (tail.col, tail.row)
+    }
 
+    pub fn segments(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.parts.iter().enumerate().map(|(i, part)| Segment {
+            col: part.col,
+            row: part.row,
+            incoming: self
+                .parts
+                .get(i + 1)
+                .and_then(|behind| Direction::between(behind, part)),
+            outgoing: match i {
+                0 => Some(self.direction),
+                _ => Direction::between(part, &self.parts[i - 1]),
+            },
+        })
+    }
+
+    pub(crate) fn draw(&self, board: &mut Board) {
         for (i, c) in self.parts.iter().enumerate().rev() {
             if i == 0 {
-                board.set_cell(c.col, c.row, CellType::SnakeHead);
+                board.set_cell(c.col, c.row, CellType::SnakeHead(self.id));
             } else {
-                board.set_cell(c.col, c.row, CellType::Snake);
+                board.set_cell(c.col, c.row, CellType::Snake(self.id));
             }
         }
     }
 
-    pub fn update_movement(&mut self, board: &mut Board) {
-        match self.direction {
-            Direction::Up => self.move_up(board),
-            Direction::Down => self.move_down(board),
-            Direction::Left => self.move_left(board),
-            Direction::Right => self.move_right(board),
-        };
+    pub(crate) fn erase(&self, board: &mut Board) {
+        for c in &self.parts {
+            board.set_cell(c.col, c.row, CellType::Empty);
+        }
+    }
+
+    pub(crate) fn update(&mut self, board: &mut Board) -> Option<Food> {
+        let head = self.parts.front().unwrap();
+        let eaten = board.food_at(head.col, head.row).copied();
+
+        if let Some(food) = eaten {
+            for _ in 0..food.kind().growth() {
+                self.grow(board);
+            }
+            for _ in 0..food.kind().shrink() {
+                self.shrink(board);
+            }
+            board.emit(GameEvent::FoodEaten(food.kind()));
+        }
+
+        for (i, c) in self.parts.iter().take(2).enumerate().rev() {
+            if i == 0 {
+                board.set_cell(c.col, c.row, CellType::SnakeHead(self.id));
+            } else {
+                board.set_cell(c.col, c.row, CellType::Snake(self.id));
+            }
+        }
+
+        if eaten.is_some() {
+            board.replenish_food();
+            if !board.has_food() {
+                board.win_game();
+            }
+        }
+
+        eaten
+    }
+
+    /// Applies the next queued turn and returns the cell the head moves into,
+    /// `None` when a solid edge is in the way.
+    pub(crate) fn next_head(&mut self, board: &Board) -> Option<(usize, usize)> {
+        if let Some(direction) = self.turns.pop_front() {
+            self.direction = direction;
+        }
+
+        let head = self.parts.front().unwrap();
+        board.neighbor(head.col, head.row, self.direction)
     }
 
-    pub fn score(&self) -> usize {
-        self.parts.len() - 4
+    pub(crate) fn advance_to(&mut self, col: usize, row: usize, board: &mut Board) {
+        self.parts.push_front(Part { col, row });
+        self.pop_end(board);
     }
 }